@@ -1,4 +1,13 @@
-use crate::email::{Action, Email, GoToAction, Greeting, Table};
+use std::borrow::Cow;
+
+use crate::email::{
+    Action, Block, Callout, Direction, Email, GoToAction, Greeting, Image, List, Table, Text,
+    VerificationCode,
+};
+use crate::markup::{
+    ConfirmAction, EventReservation, HttpActionHandler, Invoice, Markup, Order, ParcelDelivery,
+    SaveAction, ViewAction,
+};
 
 /// Email Builder
 ///
@@ -6,23 +15,15 @@ use crate::email::{Action, Email, GoToAction, Greeting, Table};
 /// use mailgen::{Action, EmailBuilder, GoToAction, Greeting};
 ///
 /// let email = EmailBuilder::new()
-///     .greeting(Greeting::Name("person name"))
+///     .greeting(Greeting::Name("person name".into()))
 ///     .intro("test intro")
 ///     .intro("another intro")
 ///     .dictionary("test key", "test value")
 ///     .dictionary("test key 2", "test value 2")
-///     .action(Action {
-///         text: "Test Action",
-///         link: "https://test.com/action",
-///         color: Some(("black", "white")),
-///         ..Default::default()
-///     })
-///     .action(Action {
-///         text: "Test Action 2",
-///         link: "https://test.com/action2",
-///         instructions: Some("test instruction"),
-///         ..Default::default()
-///     })
+///     .action(Action::new("Test Action", "https://test.com/action").color("black", "white"))
+///     .action(
+///         Action::new("Test Action 2", "https://test.com/action2").instructions("test instruction"),
+///     )
 ///     .go_to_action("Quick Action", "https://test.com/quick", "Perform this action directly from your gmail inbox")
 ///     .outro("test outro 1")
 ///     .outro("test outro 2")
@@ -31,15 +32,20 @@ use crate::email::{Action, Email, GoToAction, Greeting, Table};
 /// ```
 #[derive(Clone, Default)]
 pub struct EmailBuilder<'a> {
-    summary: Option<&'a str>,
+    subject: Option<Text<'a>>,
+    preheader: Option<Text<'a>>,
+    summary: Option<Text<'a>>,
+    hero: Option<Image<'a>>,
     greeting: Option<Greeting<'a>>,
-    intros: Option<Vec<&'a str>>,
-    dictionary: Option<Vec<(&'a str, &'a str)>>,
-    tables: Option<Vec<Table<'a>>>,
-    actions: Option<Vec<Action<'a>>>,
-    outros: Option<Vec<&'a str>>,
-    signature: Option<&'a str>,
+    blocks: Vec<Block<'a>>,
+    /// Number of leading intro and trailing outro paragraphs in `blocks`
+    intros: usize,
+    outros: usize,
+    signature: Option<Text<'a>>,
     go_to_action: Option<GoToAction<'a>>,
+    markup: Option<Vec<Markup<'a>>>,
+    direction: Option<Direction>,
+    locale: Option<Cow<'a, str>>,
 }
 
 impl<'a> EmailBuilder<'a> {
@@ -47,17 +53,39 @@ impl<'a> EmailBuilder<'a> {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            greeting: Some(Greeting::Custom("Hey")),
-            signature: Some("Yours truly,"),
+            greeting: Some(Greeting::Default),
 
             ..Default::default()
         }
     }
 
-    /// E-Mail summary, gets rendered in preview box on most email clients
+    /// Subject line of the message, defaults to the summary
+    #[must_use]
+    pub fn subject<T: Into<Text<'a>>>(mut self, v: T) -> Self {
+        self.subject = Some(v.into());
+        self
+    }
+
+    /// Preview text shown next to the subject in the inbox, hidden in the email itself.
+    /// Defaults to the summary
+    #[must_use]
+    pub fn preheader<T: Into<Text<'a>>>(mut self, v: T) -> Self {
+        self.preheader = Some(v.into());
+        self
+    }
+
+    /// E-Mail summary, displayed at the top of the text version and used as inbox preview
+    /// unless a preheader is set
+    #[must_use]
+    pub fn summary<T: Into<Text<'a>>>(mut self, v: T) -> Self {
+        self.summary = Some(v.into());
+        self
+    }
+
+    /// Full-width image below the masthead
     #[must_use]
-    pub fn summary(mut self, v: &'a str) -> Self {
-        self.summary = Some(v);
+    pub fn hero(mut self, image: Image<'a>) -> Self {
+        self.hero = Some(image);
         self
     }
 
@@ -68,105 +96,187 @@ impl<'a> EmailBuilder<'a> {
         self
     }
 
-    /// Intro sentences, first displayed in the email
+    /// Add a content block, blocks are rendered in the order they are added
+    /// (between the intros and the outros)
     #[must_use]
-    pub fn intro(mut self, v: &'a str) -> Self {
-        match &mut self.intros {
-            Some(intros) => intros.push(v),
-            None => self.intros = Some(vec![v]),
-        }
+    pub fn block<B: Into<Block<'a>>>(mut self, block: B) -> Self {
+        let index = self.blocks.len() - self.outros;
+        self.blocks.insert(index, block.into());
         self
     }
 
-    /// Intro sentences, first displayed in the email
+    /// Set all content blocks of the email, replacing the intros and outros as well
     #[must_use]
-    pub fn set_intros(mut self, intros: Vec<&'a str>) -> Self {
-        self.intros = Some(intros);
+    pub fn set_blocks(mut self, blocks: Vec<Block<'a>>) -> Self {
+        self.blocks = blocks;
+        self.intros = 0;
+        self.outros = 0;
         self
     }
 
-    /// A list of key+value (useful for displaying parameters/settings/personal info)
+    /// Add a paragraph
     #[must_use]
-    pub fn dictionary(mut self, key: &'a str, value: &'a str) -> Self {
-        match &mut self.dictionary {
-            Some(dictionary) => dictionary.push((key, value)),
-            None => self.dictionary = Some(vec![(key, value)]),
-        }
+    pub fn paragraph<T: Into<Text<'a>>>(self, text: T) -> Self {
+        self.block(Block::paragraph(text))
+    }
+
+    /// Intro sentences, first displayed in the email
+    #[must_use]
+    pub fn intro<T: Into<Text<'a>>>(mut self, v: T) -> Self {
+        self.blocks.insert(self.intros, Block::paragraph(v));
+        self.intros += 1;
         self
     }
 
-    /// A list of key+value (useful for displaying parameters/settings/personal info)
+    /// Intro sentences, first displayed in the email
     #[must_use]
-    pub fn set_dictionary(mut self, dictionary: Vec<(&'a str, &'a str)>) -> Self {
-        self.dictionary = Some(dictionary);
+    pub fn set_intros<T: Into<Text<'a>>>(mut self, intros: Vec<T>) -> Self {
+        let end = self.intros;
+        self.intros = intros.len();
+        self.blocks
+            .splice(..end, intros.into_iter().map(Block::paragraph));
         self
     }
 
-    /// Actions are a list of actions that the user will be able to execute via a button click
+    /// A list of key+value (useful for displaying parameters/settings/personal info).
+    /// Extends the dictionary if it is the last block, otherwise starts a new one
     #[must_use]
-    pub fn action(mut self, action: Action<'a>) -> Self {
-        match &mut self.actions {
-            Some(actions) => actions.push(action),
-            None => self.actions = Some(vec![action]),
+    pub fn dictionary<K: Into<Text<'a>>, T: Into<Text<'a>>>(mut self, key: K, value: T) -> Self {
+        let entry = (key.into(), value.into());
+        let end = self.blocks.len() - self.outros;
+        match self.blocks[self.intros..end].last_mut() {
+            Some(Block::Dictionary { entries }) => entries.push(entry),
+            _ => self.blocks.insert(
+                end,
+                Block::Dictionary {
+                    entries: vec![entry],
+                },
+            ),
         }
         self
     }
 
+    /// Replace all dictionaries with one at the position of the first
+    #[must_use]
+    pub fn set_dictionary<K: Into<Text<'a>>, T: Into<Text<'a>>>(
+        self,
+        dictionary: Vec<(K, T)>,
+    ) -> Self {
+        let entries = dictionary
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        self.replace_blocks(
+            |block| matches!(block, Block::Dictionary { .. }),
+            vec![Block::Dictionary { entries }],
+        )
+    }
+
     /// Actions are a list of actions that the user will be able to execute via a button click
     #[must_use]
-    pub fn set_actions(mut self, actions: Vec<Action<'a>>) -> Self {
-        self.actions = Some(actions);
-        self
+    pub fn action(self, action: Action<'a>) -> Self {
+        self.block(action)
+    }
+
+    /// Replace all actions, at the position of the first one
+    #[must_use]
+    pub fn set_actions(self, actions: Vec<Action<'a>>) -> Self {
+        self.replace_blocks(
+            |block| matches!(block, Block::Action(_)),
+            actions.into_iter().map(Block::Action).collect(),
+        )
+    }
+
+    /// Add a verification code (e.g. a one-time login code)
+    #[must_use]
+    pub fn code(self, code: VerificationCode<'a>) -> Self {
+        self.block(code)
+    }
+
+    /// Add a highlighted box (e.g. 'This link expires in 24 hours')
+    #[must_use]
+    pub fn callout(self, callout: Callout<'a>) -> Self {
+        self.block(callout)
+    }
+
+    /// Add a bulleted or numbered list
+    #[must_use]
+    pub fn list(self, list: List<'a>) -> Self {
+        self.block(list)
+    }
+
+    /// Add an image
+    #[must_use]
+    pub fn image(self, image: Image<'a>) -> Self {
+        self.block(image)
     }
 
     /// Outro sentences, last displayed in the email
     #[must_use]
-    pub fn outro(mut self, outro: &'a str) -> Self {
-        match &mut self.outros {
-            Some(outros) => outros.push(outro),
-            None => self.outros = Some(vec![outro]),
-        }
+    pub fn outro<T: Into<Text<'a>>>(mut self, outro: T) -> Self {
+        self.blocks.push(Block::paragraph(outro));
+        self.outros += 1;
         self
     }
 
     /// Outro sentences, last displayed in the email
     #[must_use]
-    pub fn set_outros(mut self, outros: Vec<&'a str>) -> Self {
-        self.outros = Some(outros);
+    pub fn set_outros<T: Into<Text<'a>>>(mut self, outros: Vec<T>) -> Self {
+        let start = self.blocks.len() - self.outros;
+        self.outros = outros.len();
+        self.blocks
+            .splice(start.., outros.into_iter().map(Block::paragraph));
         self
     }
 
-    /// Signature for the contacted person (default to 'Yours truly')
+    /// Signature for the contacted person, defaults to the signature of the locale ('Yours truly,').
+    /// An empty signature is omitted
     #[must_use]
-    pub fn signature(mut self, signature: &'a str) -> Self {
-        self.signature = Some(signature);
+    pub fn signature<T: Into<Text<'a>>>(mut self, signature: T) -> Self {
+        self.signature = Some(signature.into());
         self
     }
 
     /// Add a table to the email
     #[must_use]
-    pub fn table(mut self, table: Table<'a>) -> Self {
-        match &mut self.tables {
-            Some(tables) => tables.push(table),
-            None => self.tables = Some(vec![table]),
-        }
-        self
+    pub fn table(self, table: Table<'a>) -> Self {
+        self.block(table)
     }
 
-    /// Set all tables for the email
+    /// Replace all tables, at the position of the first one
     #[must_use]
-    pub fn set_tables(mut self, tables: Vec<Table<'a>>) -> Self {
-        self.tables = Some(tables);
+    pub fn set_tables(self, tables: Vec<Table<'a>>) -> Self {
+        self.replace_blocks(
+            |block| matches!(block, Block::Table(_)),
+            tables.into_iter().map(Block::Table).collect(),
+        )
+    }
+
+    /// Replaces the matching blocks at the position of the first match, or appends them
+    /// before the outros
+    fn replace_blocks(mut self, matches: fn(&Block<'a>) -> bool, blocks: Vec<Block<'a>>) -> Self {
+        let index = self
+            .blocks
+            .iter()
+            .position(matches)
+            .unwrap_or(self.blocks.len() - self.outros);
+        self.blocks.retain(|block| !matches(block));
+        self.blocks.splice(index..index, blocks);
         self
     }
 
     /// Set a Gmail Go-To Action for the email
     #[must_use]
-    pub fn go_to_action(mut self, text: &'a str, link: &'a str, description: &'a str) -> Self {
+    pub fn go_to_action<T: Into<Text<'a>>, S: Into<Cow<'a, str>>, D: Into<Text<'a>>>(
+        mut self,
+        text: T,
+        link: S,
+        description: D,
+    ) -> Self {
         self.go_to_action = Some(GoToAction {
-            text,
-            link,
-            description,
+            text: text.into(),
+            link: link.into(),
+            description: description.into(),
         });
         self
     }
@@ -178,19 +288,107 @@ impl<'a> EmailBuilder<'a> {
         self
     }
 
+    /// Add schema.org markup to the email
+    #[must_use]
+    pub fn markup<M: Into<Markup<'a>>>(mut self, markup: M) -> Self {
+        let markup = markup.into();
+        match &mut self.markup {
+            Some(markups) => markups.push(markup),
+            None => self.markup = Some(vec![markup]),
+        }
+        self
+    }
+
+    /// Set all schema.org markup of the email
+    #[must_use]
+    pub fn set_markup(mut self, markup: Vec<Markup<'a>>) -> Self {
+        self.markup = Some(markup);
+        self
+    }
+
+    /// Add a one-click confirm action (e.g. approving an expense)
+    #[must_use]
+    pub fn confirm_action<S: Into<Cow<'a, str>>>(self, name: S, url: S, description: S) -> Self {
+        self.markup(ConfirmAction {
+            name: name.into(),
+            handler: HttpActionHandler { url: url.into() },
+            description: Some(description.into()),
+        })
+    }
+
+    /// Add a one-click save action (e.g. saving a coupon)
+    #[must_use]
+    pub fn save_action<S: Into<Cow<'a, str>>>(self, name: S, url: S, description: S) -> Self {
+        self.markup(SaveAction {
+            name: name.into(),
+            handler: HttpActionHandler { url: url.into() },
+            description: Some(description.into()),
+        })
+    }
+
+    /// Add a view action linking to a page where the user can take action
+    #[must_use]
+    pub fn view_action<S: Into<Cow<'a, str>>>(self, name: S, url: S, description: S) -> Self {
+        self.markup(ViewAction {
+            name: name.into(),
+            url: url.into(),
+            description: Some(description.into()),
+        })
+    }
+
+    /// Add order markup (order confirmations)
+    #[must_use]
+    pub fn order(self, order: Order<'a>) -> Self {
+        self.markup(order)
+    }
+
+    /// Add parcel delivery markup (shipping notifications)
+    #[must_use]
+    pub fn parcel_delivery(self, delivery: ParcelDelivery<'a>) -> Self {
+        self.markup(delivery)
+    }
+
+    /// Add event reservation markup
+    #[must_use]
+    pub fn event_reservation(self, reservation: EventReservation<'a>) -> Self {
+        self.markup(reservation)
+    }
+
+    /// Add invoice markup
+    #[must_use]
+    pub fn invoice(self, invoice: Invoice<'a>) -> Self {
+        self.markup(invoice)
+    }
+
+    /// Text direction, defaults to the direction of the [`Mailgen`](crate::Mailgen)
+    #[must_use]
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Locale of the built-in strings (e.g. `de` or `fr-CA`), defaults to the locale of the [`Mailgen`](crate::Mailgen)
+    #[must_use]
+    pub fn locale<S: Into<Cow<'a, str>>>(mut self, locale: S) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
     /// Build the email
     #[must_use]
     pub fn build(self) -> Email<'a> {
         Email {
+            subject: self.subject,
+            preheader: self.preheader,
             summary: self.summary,
+            hero: self.hero,
             greeting: self.greeting,
-            intros: self.intros,
-            dictionary: self.dictionary,
-            tables: self.tables,
-            actions: self.actions,
-            outros: self.outros,
+            blocks: self.blocks,
             signature: self.signature,
             go_to_action: self.go_to_action,
+            markup: self.markup,
+            direction: self.direction,
+            locale: self.locale,
         }
     }
 }
@@ -198,12 +396,12 @@ impl<'a> EmailBuilder<'a> {
 #[cfg(test)]
 mod tests {
     use crate::email::Greeting;
-    use crate::EmailBuilder;
+    use crate::{Action, Block, Email, EmailBuilder};
 
     #[test]
     fn usage() {
         let _email = EmailBuilder::new()
-            .greeting(Greeting::Custom("custom greeting"))
+            .greeting(Greeting::Custom("custom greeting".into()))
             .intro("test")
             .build();
 
@@ -213,10 +411,52 @@ mod tests {
             .dictionary("test key 2", "test value 2");
 
         {
-            let greeting = Greeting::Name("Test greeting");
+            let greeting = Greeting::Name("Test greeting".into());
             email = email.greeting(greeting);
         }
 
         let _email = email.build();
     }
+
+    fn paragraphs(email: &Email) -> Vec<String> {
+        email
+            .blocks
+            .iter()
+            .map(|block| match block {
+                Block::Paragraph { text } => text.to_string(),
+                _ => "block".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn intros_and_outros() {
+        let email = EmailBuilder::new()
+            .intro("a")
+            .outro("old")
+            .set_outros(vec!["new"])
+            .build();
+        assert_eq!(paragraphs(&email), ["a", "new"]);
+
+        let email = EmailBuilder::new()
+            .intro("a")
+            .set_outros(vec!["b"])
+            .set_intros(vec!["c"])
+            .build();
+        assert_eq!(paragraphs(&email), ["c", "b"]);
+
+        let email = EmailBuilder::new()
+            .outro("bye")
+            .action(Action::new("Open", "https://test.com"))
+            .intro("hi")
+            .dictionary("key", "value")
+            .paragraph("body")
+            .set_intros(vec!["hello", "there"])
+            .set_outros(Vec::<&str>::new())
+            .build();
+        assert_eq!(
+            paragraphs(&email),
+            ["hello", "there", "block", "block", "body"]
+        );
+    }
 }
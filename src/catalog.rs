@@ -0,0 +1,41 @@
+//! Translation of [`CatalogMessage`]s with Fluent bundles
+
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+
+use crate::{CatalogMessage, MessageArg, Text};
+
+/// Fluent bundle of one locale, thread safe so a [`Mailgen`](crate::Mailgen) can be shared
+pub type FluentBundle = fluent_bundle::concurrent::FluentBundle<FluentResource>;
+
+/// Translates catalog messages, other texts are returned as is
+pub(crate) fn translate<'a>(bundle: &FluentBundle, text: Text<'a>) -> Text<'a> {
+    match text {
+        Text::Message(message) => Text::Plain(format(bundle, &message).into()),
+        text => text,
+    }
+}
+
+/// Formats a message, messages missing from the bundle are replaced with their id
+fn format(bundle: &FluentBundle, message: &CatalogMessage) -> String {
+    let Some(pattern) = bundle
+        .get_message(&message.id)
+        .and_then(|bundle_message| bundle_message.value())
+    else {
+        return message.id.to_string();
+    };
+
+    let mut args = FluentArgs::new();
+    for (name, value) in &message.args {
+        let value = match value {
+            MessageArg::Number(number) => FluentValue::from(*number),
+            MessageArg::Text(text) => FluentValue::from(text.as_ref()),
+        };
+        args.set(name.as_ref(), value);
+    }
+
+    // formatting errors (e.g. missing arguments) still produce a readable message
+    let mut errors = Vec::new();
+    bundle
+        .format_pattern(pattern, Some(&args), &mut errors)
+        .into_owned()
+}
@@ -1,32 +1,587 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::markup::Markup;
 
 /// Email for rendering
-#[derive(Debug, Clone, Serialize)]
+///
+/// Emails can be deserialized (e.g. from a JSON job payload), `Email<'static>` owns all of its content.
+/// Besides `blocks`, payloads can use the fixed sections `intros`, `dictionary`, `tables`, `actions`
+/// and `outros`, which are rendered in this order around the blocks (before the outros).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "EmailRepr<'a>")]
 pub struct Email<'a> {
-    /// E-Mail summary, gets rendered in preview box on most email clients
-    pub summary: Option<&'a str>,
+    /// Subject line of the message, defaults to the summary
+    pub subject: Option<Text<'a>>,
+    /// Preview text shown next to the subject in the inbox, hidden in the email itself.
+    /// Defaults to the summary
+    pub preheader: Option<Text<'a>>,
+    /// E-Mail summary, displayed at the top of the text version and used as inbox preview
+    /// unless a preheader is set
+    pub summary: Option<Text<'a>>,
+    /// Full-width image below the masthead
+    pub hero: Option<Image<'a>>,
     /// E-Mail greeting
     pub greeting: Option<Greeting<'a>>,
-    /// Intro sentences, first displayed in the email
-    pub intros: Option<Vec<&'a str>>,
-    /// A list of key+value (useful for displaying parameters/settings/personal info)
-    pub dictionary: Option<Vec<(&'a str, &'a str)>>,
-    /// Table data to display in the email
-    pub tables: Option<Vec<Table<'a>>>,
-    /// Actions are a list of actions that the user will be able to execute via a button click
-    pub actions: Option<Vec<Action<'a>>>,
-    /// Outro sentences, last displayed in the email
-    pub outros: Option<Vec<&'a str>>,
-    /// Signature for the contacted person (default to 'Yours truly')
-    pub signature: Option<&'a str>,
+    /// Content of the email (paragraphs, lists, dictionaries, tables, actions, ...), rendered in order
+    pub blocks: Vec<Block<'a>>,
+    /// Signature for the contacted person, defaults to the signature of the locale ('Yours truly,').
+    /// An empty signature is omitted
+    pub signature: Option<Text<'a>>,
     /// Gmail Go-To Action for providing actionable content directly in the inbox
     pub go_to_action: Option<GoToAction<'a>>,
+    /// schema.org markup for inbox actions and rich cards (orders, deliveries, reservations, invoices)
+    pub markup: Option<Vec<Markup<'a>>>,
+    /// Text direction, defaults to the direction of the [`Mailgen`](crate::Mailgen)
+    pub direction: Option<Direction>,
+    /// Locale of the built-in strings (e.g. `de` or `fr-CA`), defaults to the locale of the [`Mailgen`](crate::Mailgen)
+    pub locale: Option<Cow<'a, str>>,
+}
+
+#[derive(Deserialize)]
+struct EmailRepr<'a> {
+    subject: Option<Text<'a>>,
+    preheader: Option<Text<'a>>,
+    summary: Option<Text<'a>>,
+    hero: Option<Image<'a>>,
+    greeting: Option<Greeting<'a>>,
+    intros: Option<Vec<Text<'a>>>,
+    dictionary: Option<Vec<(Text<'a>, Text<'a>)>>,
+    tables: Option<Vec<Table<'a>>>,
+    actions: Option<Vec<Action<'a>>>,
+    #[serde(default)]
+    blocks: Vec<Block<'a>>,
+    outros: Option<Vec<Text<'a>>>,
+    signature: Option<Text<'a>>,
+    go_to_action: Option<GoToAction<'a>>,
+    markup: Option<Vec<Markup<'a>>>,
+    direction: Option<Direction>,
+    locale: Option<Cow<'a, str>>,
+}
+
+impl<'a> From<EmailRepr<'a>> for Email<'a> {
+    fn from(value: EmailRepr<'a>) -> Self {
+        let intros = value.intros.into_iter().flatten().map(Block::paragraph);
+        let dictionary = value
+            .dictionary
+            .map(|entries| Block::Dictionary { entries });
+        let tables = value.tables.into_iter().flatten().map(Block::Table);
+        let actions = value.actions.into_iter().flatten().map(Block::Action);
+        let outros = value.outros.into_iter().flatten().map(Block::paragraph);
+
+        Self {
+            subject: value.subject,
+            preheader: value.preheader,
+            summary: value.summary,
+            hero: value.hero,
+            greeting: value.greeting,
+            blocks: intros
+                .chain(dictionary)
+                .chain(tables)
+                .chain(actions)
+                .chain(value.blocks)
+                .chain(outros)
+                .collect(),
+            signature: value.signature,
+            go_to_action: value.go_to_action,
+            markup: value.markup,
+            direction: value.direction,
+            locale: value.locale,
+        }
+    }
+}
+
+impl<'a> Email<'a> {
+    /// Subject line of the message: the explicit subject or else the summary
+    pub fn subject_line(&self) -> Option<&Text<'a>> {
+        self.subject.as_ref().or(self.summary.as_ref())
+    }
+
+    /// schema.org markup of the email as JSON-LD, safe to embed in a `<script type="application/ld+json">` tag.
+    /// Returns `None` if the email has no markup.
+    pub fn json_ld(&self) -> Result<Option<String>, serde_json::Error> {
+        crate::markup::json_ld(self)
+    }
+}
+
+/// Content block of an email
+///
+/// Deserializes from an object tagged with its `type`, e.g. `{"type": "paragraph", "text": "..."}`
+/// or `{"type": "action", "text": "...", "link": "..."}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Block<'a> {
+    /// Paragraph of text
+    Paragraph { text: Text<'a> },
+    /// A list of key+value (useful for displaying parameters/settings/personal info)
+    Dictionary { entries: Vec<(Text<'a>, Text<'a>)> },
+    /// Table data
+    Table(Table<'a>),
+    /// Button the user can click
+    Action(Action<'a>),
+    /// Verification code (e.g. a one-time login code)
+    Code(VerificationCode<'a>),
+    /// Highlighted box (e.g. 'Your payment failed')
+    Callout(Callout<'a>),
+    /// Bulleted or numbered list
+    List(List<'a>),
+    /// Image, optionally linked
+    Image(Image<'a>),
+}
+
+impl<'a> Block<'a> {
+    /// Paragraph of text
+    pub fn paragraph<T: Into<Text<'a>>>(text: T) -> Self {
+        Block::Paragraph { text: text.into() }
+    }
+}
+
+impl<'a> From<Table<'a>> for Block<'a> {
+    fn from(value: Table<'a>) -> Self {
+        Block::Table(value)
+    }
+}
+
+impl<'a> From<Action<'a>> for Block<'a> {
+    fn from(value: Action<'a>) -> Self {
+        Block::Action(value)
+    }
+}
+
+impl<'a> From<VerificationCode<'a>> for Block<'a> {
+    fn from(value: VerificationCode<'a>) -> Self {
+        Block::Code(value)
+    }
+}
+
+impl<'a> From<Callout<'a>> for Block<'a> {
+    fn from(value: Callout<'a>) -> Self {
+        Block::Callout(value)
+    }
+}
+
+impl<'a> From<List<'a>> for Block<'a> {
+    fn from(value: List<'a>) -> Self {
+        Block::List(value)
+    }
+}
+
+impl<'a> From<Image<'a>> for Block<'a> {
+    fn from(value: Image<'a>) -> Self {
+        Block::Image(value)
+    }
+}
+
+/// Text content of an email
+///
+/// Plain text is escaped by the theme before rendering. Use [`Text::Html`] only
+/// for trusted fragments, they are inserted into the email as is.
+///
+/// Serializes as `{"plain": "..."}`, `{"html": "..."}`, `{"markdown": "..."}` or
+/// `{"message": {"id": "...", "args": {...}}}` and deserializes from these or from a plain string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "TextRepr<'a>", rename_all = "lowercase")]
+pub enum Text<'a> {
+    /// Plain text, escaped when rendered
+    Plain(Cow<'a, str>),
+    /// Trusted raw HTML, rendered without escaping
+    Html(Cow<'a, str>),
+    /// Message of a message catalog, translated for the locale of the email when rendered
+    /// (requires the `fluent` feature). Unresolved messages render as their id
+    Message(CatalogMessage<'a>),
+    /// Markdown for inline formatting and links, rendered to sanitized HTML (requires the
+    /// `markdown` feature). Raw HTML is escaped. Without the feature it is rendered as plain text
+    Markdown(Cow<'a, str>),
+}
+
+impl Default for Text<'_> {
+    fn default() -> Self {
+        Text::Plain(Cow::Borrowed(""))
+    }
+}
+
+impl<'a> From<&'a str> for Text<'a> {
+    fn from(value: &'a str) -> Self {
+        Text::Plain(value.into())
+    }
+}
+
+impl From<String> for Text<'_> {
+    fn from(value: String) -> Self {
+        Text::Plain(value.into())
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Text<'a> {
+    fn from(value: Cow<'a, str>) -> Self {
+        Text::Plain(value)
+    }
+}
+
+impl<'a> From<CatalogMessage<'a>> for Text<'a> {
+    fn from(value: CatalogMessage<'a>) -> Self {
+        Text::Message(value)
+    }
+}
+
+/// Reference to a message of a message catalog (e.g. a Fluent bundle) with its arguments
+///
+/// ```
+/// use mailgen::{CatalogMessage, EmailBuilder};
+///
+/// let email = EmailBuilder::new()
+///     .intro(CatalogMessage::new("order-shipped").arg("order", "#42").arg("items", 3))
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogMessage<'a> {
+    /// Message id
+    pub id: Cow<'a, str>,
+    /// Arguments of the message
+    #[serde(default)]
+    pub args: BTreeMap<Cow<'a, str>, MessageArg<'a>>,
+}
+
+impl<'a> CatalogMessage<'a> {
+    pub fn new<S: Into<Cow<'a, str>>>(id: S) -> Self {
+        Self {
+            id: id.into(),
+            args: BTreeMap::new(),
+        }
+    }
+
+    /// Add an argument to the message
+    #[must_use]
+    pub fn arg<S: Into<Cow<'a, str>>, V: Into<MessageArg<'a>>>(
+        mut self,
+        name: S,
+        value: V,
+    ) -> Self {
+        self.args.insert(name.into(), value.into());
+        self
+    }
+}
+
+/// Argument of a [`CatalogMessage`], numbers select plural forms
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageArg<'a> {
+    Number(f64),
+    Text(Cow<'a, str>),
+}
+
+impl<'a> From<&'a str> for MessageArg<'a> {
+    fn from(value: &'a str) -> Self {
+        MessageArg::Text(value.into())
+    }
+}
+
+impl From<String> for MessageArg<'_> {
+    fn from(value: String) -> Self {
+        MessageArg::Text(value.into())
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for MessageArg<'a> {
+    fn from(value: Cow<'a, str>) -> Self {
+        MessageArg::Text(value)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($number:ty),*) => {
+        $(
+            impl From<$number> for MessageArg<'_> {
+                fn from(value: $number) -> Self {
+                    MessageArg::Number(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(i8, i16, i32, u8, u16, u32, f32, f64);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TextRepr<'a> {
+    Plain(Cow<'a, str>),
+    Tagged(TaggedText<'a>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TaggedText<'a> {
+    Plain(Cow<'a, str>),
+    Html(Cow<'a, str>),
+    Markdown(Cow<'a, str>),
+    Message(CatalogMessage<'a>),
+}
+
+impl<'a> From<TextRepr<'a>> for Text<'a> {
+    fn from(value: TextRepr<'a>) -> Self {
+        match value {
+            TextRepr::Plain(text) | TextRepr::Tagged(TaggedText::Plain(text)) => Text::Plain(text),
+            TextRepr::Tagged(TaggedText::Html(html)) => Text::Html(html),
+            TextRepr::Tagged(TaggedText::Markdown(markdown)) => Text::Markdown(markdown),
+            TextRepr::Tagged(TaggedText::Message(message)) => Text::Message(message),
+        }
+    }
+}
+
+impl Display for Text<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Text::Plain(text) | Text::Html(text) | Text::Markdown(text) => f.write_str(text),
+            Text::Message(message) => f.write_str(&message.id),
+        }
+    }
+}
+
+/// Text direction of an email
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Left-to-right
+    #[default]
+    Ltr,
+    /// Right-to-left (e.g. Arabic, Hebrew), mirrors the layout
+    Rtl,
 }
 
-/// Column configuration for table
+/// Horizontal alignment of a table column
+///
+/// Left and right are mirrored in right-to-left emails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    fn parse(value: &str) -> Self {
+        match value {
+            "center" => Alignment::Center,
+            "right" => Alignment::Right,
+            _ => Alignment::Left,
+        }
+    }
+}
+
+/// Column of a table
+///
+/// Deserializes from a key or from an object, the label defaults to the key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "ColumnRepr<'a>")]
+pub struct Column<'a> {
+    /// Key used to match cells of keyed rows against the column
+    pub key: Cow<'a, str>,
+    /// Header label, defaults to the key
+    pub label: Text<'a>,
+    /// Custom width (e.g. `20%` or `100px`)
+    pub width: Option<Cow<'a, str>>,
+    /// Alignment of the header and the cells
+    pub align: Alignment,
+}
+
+impl<'a> Column<'a> {
+    /// Column with the key as header label
+    pub fn new<S: Into<Cow<'a, str>>>(key: S) -> Self {
+        let key = key.into();
+        Self {
+            label: Text::Plain(key.clone()),
+            key,
+            width: None,
+            align: Alignment::Left,
+        }
+    }
+
+    /// Set the header label
+    #[must_use]
+    pub fn label<T: Into<Text<'a>>>(mut self, label: T) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Set a custom width (e.g. `20%` or `100px`)
+    #[must_use]
+    pub fn width<S: Into<Cow<'a, str>>>(mut self, width: S) -> Self {
+        self.width = Some(width.into());
+        self
+    }
+
+    /// Set the alignment
+    #[must_use]
+    pub fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColumnRepr<'a> {
+    Key(Cow<'a, str>),
+    Column {
+        key: Cow<'a, str>,
+        label: Option<Text<'a>>,
+        width: Option<Cow<'a, str>>,
+        #[serde(default)]
+        align: Alignment,
+    },
+}
+
+impl<'a> From<ColumnRepr<'a>> for Column<'a> {
+    fn from(value: ColumnRepr<'a>) -> Self {
+        match value {
+            ColumnRepr::Key(key) => Column::new(key),
+            ColumnRepr::Column {
+                key,
+                label,
+                width,
+                align,
+            } => Column {
+                label: label.unwrap_or_else(|| Text::Plain(key.clone())),
+                key,
+                width,
+                align,
+            },
+        }
+    }
+}
+
+/// Emphasis of a table cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Emphasis {
+    /// Highlighted, e.g. a total
+    Bold,
+    /// Less prominent, e.g. a note or a discount
+    Muted,
+}
+
+/// Cell of a table row
+///
+/// Deserializes from a plain string or from an object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "CellRepr<'a>")]
+pub struct Cell<'a> {
+    /// Cell content
+    pub text: Text<'a>,
+    /// Optional emphasis
+    pub emphasis: Option<Emphasis>,
+    /// Makes the cell content a link
+    pub link: Option<Cow<'a, str>>,
+    /// Number of columns the cell spans
+    pub colspan: u32,
+}
+
+impl<'a> Cell<'a> {
+    pub fn new<T: Into<Text<'a>>>(text: T) -> Self {
+        Self {
+            text: text.into(),
+            emphasis: None,
+            link: None,
+            colspan: 1,
+        }
+    }
+
+    /// Render the cell in bold
+    #[must_use]
+    pub fn bold(mut self) -> Self {
+        self.emphasis = Some(Emphasis::Bold);
+        self
+    }
+
+    /// Render the cell muted
+    #[must_use]
+    pub fn muted(mut self) -> Self {
+        self.emphasis = Some(Emphasis::Muted);
+        self
+    }
+
+    /// Make the cell content a link
+    #[must_use]
+    pub fn link<S: Into<Cow<'a, str>>>(mut self, link: S) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    /// Span the cell over multiple columns
+    #[must_use]
+    pub fn colspan(mut self, colspan: u32) -> Self {
+        self.colspan = colspan.max(1);
+        self
+    }
+}
+
+impl<'a> From<&'a str> for Cell<'a> {
+    fn from(text: &'a str) -> Self {
+        Cell::new(text)
+    }
+}
+
+impl From<String> for Cell<'_> {
+    fn from(text: String) -> Self {
+        Cell::new(text)
+    }
+}
+
+impl<'a> From<Text<'a>> for Cell<'a> {
+    fn from(text: Text<'a>) -> Self {
+        Cell::new(text)
+    }
+}
+
+impl<'a> From<CatalogMessage<'a>> for Cell<'a> {
+    fn from(message: CatalogMessage<'a>) -> Self {
+        Cell::new(message)
+    }
+}
+
+fn default_colspan() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CellRepr<'a> {
+    Text(Text<'a>),
+    Cell {
+        text: Text<'a>,
+        emphasis: Option<Emphasis>,
+        link: Option<Cow<'a, str>>,
+        #[serde(default = "default_colspan")]
+        colspan: u32,
+    },
+}
+
+impl<'a> From<CellRepr<'a>> for Cell<'a> {
+    fn from(value: CellRepr<'a>) -> Self {
+        match value {
+            CellRepr::Text(text) => Cell::new(text),
+            CellRepr::Cell {
+                text,
+                emphasis,
+                link,
+                colspan,
+            } => Cell {
+                text,
+                emphasis,
+                link,
+                colspan: colspan.max(1),
+            },
+        }
+    }
+}
+
+/// Column configuration of the previous map based table format, see [`Table::from_data`]
 #[derive(Debug, Clone, Serialize)]
 pub struct TableColumns<'a> {
     /// Custom width for specific columns
@@ -36,63 +591,418 @@ pub struct TableColumns<'a> {
 }
 
 /// Table data to display in the email
-#[derive(Debug, Clone, Serialize)]
+///
+/// ```
+/// use mailgen::{Alignment, Cell, Column, Table};
+///
+/// let table = Table::new("Order Summary")
+///     .column(Column::new("item").label("Item"))
+///     .column(Column::new("price").label("Price").align(Alignment::Right))
+///     .row(["Product 1", "$10.99"])
+///     .row([Cell::new("Product 2").link("https://test.com/product-2"), "$24.99".into()])
+///     .footer_row(["Tax", "$3.60"])
+///     .footer_row([Cell::new("Total").bold(), Cell::new("$39.58").bold()]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Table<'a> {
     /// Table title
-    pub title: &'a str,
-    /// Table data rows
-    pub data: Vec<HashMap<&'a str, &'a str>>,
-    /// Column configuration
-    pub columns: Option<TableColumns<'a>>,
-}
-
-#[derive(Debug, Clone)]
-pub enum Greeting<'a> {
-    /// Displays a greeting by name. Renders as `Hey {name},`
-    Name(&'a str),
-    /// Custom greeting
-    Custom(&'a str),
+    pub title: Text<'a>,
+    /// Columns in display order
+    pub columns: Vec<Column<'a>>,
+    /// Table data rows, cells are positioned against `columns`
+    #[serde(default)]
+    pub rows: Vec<Vec<Cell<'a>>>,
+    /// Footer rows (e.g. subtotal, tax, total), displayed separated from the data rows
+    #[serde(default)]
+    pub footer: Vec<Vec<Cell<'a>>>,
 }
 
-impl Display for Greeting<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Greeting::Name(name) => write!(f, "Hey {name},"),
-            Greeting::Custom(custom) => f.write_str(custom),
+impl<'a> Table<'a> {
+    /// Empty table with a title
+    pub fn new<T: Into<Text<'a>>>(title: T) -> Self {
+        Self {
+            title: title.into(),
+            columns: Vec::new(),
+            rows: Vec::new(),
+            footer: Vec::new(),
         }
     }
-}
 
-impl Serialize for Greeting<'_> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    /// Append a column
+    #[must_use]
+    pub fn column(mut self, column: Column<'a>) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Append a row, cells are matched to the columns by position
+    #[must_use]
+    pub fn row<I, C>(mut self, cells: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<Cell<'a>>,
+    {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Append a footer row, cells are matched to the columns by position
+    #[must_use]
+    pub fn footer_row<I, C>(mut self, cells: I) -> Self
     where
-        S: serde::Serializer,
+        I: IntoIterator<Item = C>,
+        C: Into<Cell<'a>>,
     {
-        serializer.collect_str(self)
+        self.footer
+            .push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Append a row, cells are matched to the columns by key. Missing cells stay empty.
+    #[must_use]
+    pub fn keyed_row(mut self, cells: &HashMap<&'a str, &'a str>) -> Self {
+        let row = self
+            .columns
+            .iter()
+            .map(|column| Cell::new(cells.get(&*column.key).copied().unwrap_or_default()))
+            .collect();
+        self.rows.push(row);
+        self
     }
+
+    /// Migration helper for the previous map based table format.
+    ///
+    /// Maps have no order, so the columns are sorted by key. Prefer [`Table::new`] with
+    /// explicit columns to control the order. Keys are title-cased for the column labels,
+    /// like the previous template did.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use mailgen::Table;
+    ///
+    /// let table = Table::from_data("Order", vec![HashMap::from([("unit price", "$10.99")])], None);
+    /// assert_eq!(table.columns[0].key, "unit price");
+    /// assert_eq!(table.columns[0].label.to_string(), "Unit Price");
+    /// ```
+    pub fn from_data<T: Into<Text<'a>>>(
+        title: T,
+        data: Vec<HashMap<&'a str, &'a str>>,
+        columns: Option<TableColumns<'a>>,
+    ) -> Self {
+        let mut keys: Vec<&str> = data.iter().flat_map(|row| row.keys().copied()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+
+        let columns = columns.as_ref();
+        let mut table = Self::new(title);
+        for key in keys {
+            let mut column = Column::new(key).label(title_case(key));
+            if let Some(width) = columns
+                .and_then(|c| c.custom_width.as_ref())
+                .and_then(|w| w.get(key))
+            {
+                column = column.width(*width);
+            }
+            if let Some(alignment) = columns
+                .and_then(|c| c.custom_alignment.as_ref())
+                .and_then(|a| a.get(key))
+            {
+                column = column.align(Alignment::parse(alignment));
+            }
+            table = table.column(column);
+        }
+
+        for row in &data {
+            table = table.keyed_row(row);
+        }
+        table
+    }
+}
+
+/// Uppercases the first letter of every word, as the `title` filter of the previous template
+fn title_case(value: &str) -> String {
+    let mut title = String::with_capacity(value.len());
+    let mut capitalize = true;
+    for c in value.chars() {
+        if c.is_ascii_punctuation() || c.is_whitespace() {
+            title.push(c);
+            capitalize = true;
+        } else if capitalize {
+            title.extend(c.to_uppercase());
+            capitalize = false;
+        } else {
+            title.extend(c.to_lowercase());
+        }
+    }
+    title
+}
+
+/// Greetings other than [`Greeting::Custom`] are localized when rendered.
+///
+/// (De)serializes as `"default"`, `{"name": "..."}` or `{"custom": "..."}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Greeting<'a> {
+    /// Greeting of the locale ([`Strings::greeting`](crate::Strings::greeting), e.g. `Hey`)
+    Default,
+    /// Greeting by name of the locale ([`Strings::greeting_name`](crate::Strings::greeting_name),
+    /// e.g. `Hey {name},`)
+    Name(Cow<'a, str>),
+    /// Custom greeting, rendered as plain text (catalog messages are translated)
+    Custom(Text<'a>),
 }
 
 /// Action is an action the user can do on the email (click on a button)
-#[derive(Debug, Default, Clone, Serialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Action<'a> {
     /// Button text
-    pub text: &'a str,
+    pub text: Text<'a>,
     /// Button link
-    pub link: &'a str,
+    pub link: Cow<'a, str>,
 
     /// Text displayed before the button
-    pub instructions: Option<&'a str>,
+    pub instructions: Option<Text<'a>>,
     /// Custom colors for the button in the format: (color, background-color)
-    pub color: Option<(&'a str, &'a str)>,
+    pub color: Option<(Cow<'a, str>, Cow<'a, str>)>,
+}
+
+impl<'a> Action<'a> {
+    pub fn new<T: Into<Text<'a>>, S: Into<Cow<'a, str>>>(text: T, link: S) -> Self {
+        Self {
+            text: text.into(),
+            link: link.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the text displayed before the button
+    #[must_use]
+    pub fn instructions<T: Into<Text<'a>>>(mut self, instructions: T) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Set custom button colors
+    #[must_use]
+    pub fn color<S: Into<Cow<'a, str>>>(mut self, color: S, background_color: S) -> Self {
+        self.color = Some((color.into(), background_color.into()));
+        self
+    }
+}
+
+/// Verification code the user has to enter (e.g. a one-time login code),
+/// displayed large and on its own line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationCode<'a> {
+    /// The code
+    pub code: Cow<'a, str>,
+    /// Note displayed below the code, e.g. when it expires
+    pub expiry: Option<Text<'a>>,
+}
+
+impl<'a> VerificationCode<'a> {
+    pub fn new<S: Into<Cow<'a, str>>>(code: S) -> Self {
+        Self {
+            code: code.into(),
+            expiry: None,
+        }
+    }
+
+    /// Set the note displayed below the code (e.g. 'This code expires in 10 minutes.')
+    #[must_use]
+    pub fn expiry<T: Into<Text<'a>>>(mut self, expiry: T) -> Self {
+        self.expiry = Some(expiry.into());
+        self
+    }
+}
+
+/// Severity of a callout, decides its color
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Danger,
+}
+
+/// Highlighted box in the body of the email, colored by its severity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Callout<'a> {
+    #[serde(default)]
+    pub severity: Severity,
+    /// Title displayed above the text
+    pub title: Option<Text<'a>>,
+    pub text: Text<'a>,
+}
+
+impl<'a> Callout<'a> {
+    pub fn new<T: Into<Text<'a>>>(severity: Severity, text: T) -> Self {
+        Self {
+            severity,
+            title: None,
+            text: text.into(),
+        }
+    }
+
+    pub fn info<T: Into<Text<'a>>>(text: T) -> Self {
+        Self::new(Severity::Info, text)
+    }
+
+    pub fn success<T: Into<Text<'a>>>(text: T) -> Self {
+        Self::new(Severity::Success, text)
+    }
+
+    pub fn warning<T: Into<Text<'a>>>(text: T) -> Self {
+        Self::new(Severity::Warning, text)
+    }
+
+    pub fn danger<T: Into<Text<'a>>>(text: T) -> Self {
+        Self::new(Severity::Danger, text)
+    }
+
+    /// Set the title displayed above the text
+    #[must_use]
+    pub fn title<T: Into<Text<'a>>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+
+/// Bulleted or numbered list, items can have one level of nested items
+///
+/// Items deserialize from a text or an object with the `text` and `children` of the item.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct List<'a> {
+    /// Numbered instead of bulleted
+    #[serde(default)]
+    pub ordered: bool,
+    pub items: Vec<ListItem<'a>>,
+}
+
+impl<'a> List<'a> {
+    /// Empty bulleted list
+    pub fn unordered() -> Self {
+        Self::default()
+    }
+
+    /// Empty numbered list
+    pub fn ordered() -> Self {
+        Self {
+            ordered: true,
+            items: Vec::new(),
+        }
+    }
+
+    /// Append an item
+    #[must_use]
+    pub fn item<T: Into<Text<'a>>>(mut self, text: T) -> Self {
+        self.items.push(ListItem::new(text));
+        self
+    }
+
+    /// Append an item with nested items
+    #[must_use]
+    pub fn item_with_children<T: Into<Text<'a>>, C: Into<Text<'a>>>(
+        mut self,
+        text: T,
+        children: Vec<C>,
+    ) -> Self {
+        self.items.push(ListItem {
+            text: text.into(),
+            children: children.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+}
+
+/// Item of a [`List`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "ListItemRepr<'a>")]
+pub struct ListItem<'a> {
+    pub text: Text<'a>,
+    /// Nested items, numbered or bulleted like the list
+    pub children: Vec<Text<'a>>,
+}
+
+impl<'a> ListItem<'a> {
+    pub fn new<T: Into<Text<'a>>>(text: T) -> Self {
+        Self {
+            text: text.into(),
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ListItemRepr<'a> {
+    Text(Text<'a>),
+    Item {
+        text: Text<'a>,
+        #[serde(default)]
+        children: Vec<Text<'a>>,
+    },
+}
+
+impl<'a> From<ListItemRepr<'a>> for ListItem<'a> {
+    fn from(value: ListItemRepr<'a>) -> Self {
+        match value {
+            ListItemRepr::Text(text) => ListItem::new(text),
+            ListItemRepr::Item { text, children } => ListItem { text, children },
+        }
+    }
+}
+
+/// Image in the body of the email, the alt text is displayed in the text version
+///
+/// Images are scaled down to the width of the email (and on small screens) while keeping
+/// their aspect ratio.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Image<'a> {
+    /// Image URL
+    pub src: Cow<'a, str>,
+    /// Alternative text, empty for decorative images. Rendered as plain text
+    pub alt: Text<'a>,
+    /// Maximum width in pixels, defaults to the width of the email
+    pub width: Option<u32>,
+    /// Makes the image a link
+    pub link: Option<Cow<'a, str>>,
+}
+
+impl<'a> Image<'a> {
+    pub fn new<S: Into<Cow<'a, str>>, T: Into<Text<'a>>>(src: S, alt: T) -> Self {
+        Self {
+            src: src.into(),
+            alt: alt.into(),
+            width: None,
+            link: None,
+        }
+    }
+
+    /// Set the maximum width in pixels
+    #[must_use]
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Make the image a link
+    #[must_use]
+    pub fn link<S: Into<Cow<'a, str>>>(mut self, link: S) -> Self {
+        self.link = Some(link.into());
+        self
+    }
 }
 
 /// Gmail Go-To Action for providing actionable content directly in the inbox
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoToAction<'a> {
     /// Button text
-    pub text: &'a str,
+    pub text: Text<'a>,
     /// Button link
-    pub link: &'a str,
+    pub link: Cow<'a, str>,
     /// Description of the action
-    pub description: &'a str,
+    pub description: Text<'a>,
 }
@@ -14,23 +14,15 @@
 //!
 //! let email = EmailBuilder::new()
 //!     .summary("this is a test email that contains stuff to test...")
-//!     .greeting(Greeting::Name("person name"))
+//!     .greeting(Greeting::Name("person name".into()))
 //!     .intro("test intro")
 //!     .intro("another intro")
 //!     .dictionary("test key", "test value")
 //!     .dictionary("test key 2", "test value 2")
-//!     .action(Action {
-//!         text: "Test Action",
-//!         link: "https://test.com/action",
-//!         color: Some(("black", "white")),
-//!         ..Default::default()
-//!     })
-//!     .action(Action {
-//!         text: "Test Action 2",
-//!         link: "https://test.com/action2",
-//!         instructions: Some("test instruction"),
-//!         ..Default::default()
-//!     })
+//!     .action(Action::new("Test Action", "https://test.com/action").color("black", "white"))
+//!     .action(
+//!         Action::new("Test Action 2", "https://test.com/action2").instructions("test instruction"),
+//!     )
 //!     .outro("test outro 1")
 //!     .outro("test outro 2")
 //!     .signature("test signature...")
@@ -46,41 +38,416 @@
 //! ```
 
 mod builder;
+#[cfg(feature = "fluent")]
+mod catalog;
 mod email;
+#[cfg(feature = "markdown")]
+mod markdown;
+pub mod markup;
+#[cfg(feature = "message")]
+pub mod message;
+mod strings;
 pub mod themes;
+mod url;
 
 pub use builder::EmailBuilder;
-pub use email::{Action, Email, GoToAction, Greeting, Table, TableColumns};
+pub use email::{
+    Action, Alignment, Block, Callout, CatalogMessage, Cell, Column, Direction, Email, Emphasis,
+    GoToAction, Greeting, Image, List, ListItem, MessageArg, Severity, Table, TableColumns, Text,
+    VerificationCode,
+};
+pub use strings::Strings;
+
+#[cfg(feature = "fluent")]
+pub use catalog::FluentBundle;
+#[cfg(feature = "fluent")]
+pub use {fluent_bundle, unic_langid};
+
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use themes::{TemplateContext, Theme};
 
 pub struct Mailgen<T: Theme> {
     theme: T,
     branding: Branding,
+    direction: Direction,
+    locale: String,
+    strings: HashMap<String, Strings>,
+
+    #[cfg(feature = "fluent")]
+    bundles: HashMap<String, FluentBundle>,
+
+    #[cfg(feature = "message")]
+    inline_images: Vec<message::InlineImage>,
 }
 
 impl<T: Theme> Mailgen<T> {
     pub fn new(theme: T, branding: Branding) -> Self {
-        Self { theme, branding }
+        Self {
+            theme,
+            branding,
+            direction: Direction::default(),
+            locale: "en".to_string(),
+            strings: ["en", "de", "fr", "es"]
+                .into_iter()
+                .filter_map(|language| Some((language.to_string(), Strings::bundled(language)?)))
+                .collect(),
+
+            #[cfg(feature = "fluent")]
+            bundles: HashMap::new(),
+
+            #[cfg(feature = "message")]
+            inline_images: Vec::new(),
+        }
+    }
+
+    /// Text direction of all emails that don't set their own
+    #[must_use]
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Locale of all emails that don't set their own, defaults to `en`
+    #[must_use]
+    pub fn locale<S: Into<String>>(mut self, locale: S) -> Self {
+        self.locale = locale.into();
+        self
+    }
+
+    /// Add or replace the built-in strings of a locale (e.g. `it` or `de-CH`)
+    #[must_use]
+    pub fn strings<S: AsRef<str>>(mut self, locale: S, strings: Strings) -> Self {
+        let [locale, _] = strings::candidates(locale.as_ref());
+        self.strings.insert(locale, strings);
+        self
+    }
+
+    /// Add the Fluent bundle of a locale (e.g. `de` or `de-CH`), used to translate [`CatalogMessage`]s
+    /// of emails in this locale. The bundle of the language is used for other regions.
+    #[cfg(feature = "fluent")]
+    #[must_use]
+    pub fn fluent_bundle<S: AsRef<str>>(mut self, locale: S, bundle: FluentBundle) -> Self {
+        let [locale, _] = strings::candidates(locale.as_ref());
+        self.bundles.insert(locale, bundle);
+        self
+    }
+
+    /// Embed an image (e.g. the logo of the branding) into every message rendered by [`Mailgen::render_message`]
+    /// (and [`Mailgen::render_lettre_message`])
+    #[cfg(feature = "message")]
+    #[must_use]
+    pub fn inline_image(mut self, image: message::InlineImage) -> Self {
+        self.inline_images.push(image);
+        self
     }
 
     pub fn render_html(&self, email: &Email) -> Result<String, T::Error> {
+        let (email, branding, greeting, severities) = self.prepare(email);
         let context = TemplateContext {
-            email,
-            branding: &self.branding,
+            email: &email,
+            greeting: greeting.as_deref(),
+            severities: &severities,
+            branding: &branding,
+            direction: email.direction.unwrap_or(self.direction),
         };
 
         self.theme.html(&context)
     }
 
     pub fn render_text(&self, email: &Email) -> Result<String, T::Error> {
+        let (email, branding, greeting, severities) = self.prepare(email);
         let context = TemplateContext {
-            email,
-            branding: &self.branding,
+            email: &email,
+            greeting: greeting.as_deref(),
+            severities: &severities,
+            branding: &branding,
+            direction: email.direction.unwrap_or(self.direction),
         };
 
         self.theme.text(&context)
     }
+
+    /// Entry for a locale, falling back to its language and the locale of the mailgen
+    fn lookup<'m, V>(
+        &self,
+        catalog: &'m HashMap<String, V>,
+        locale: Option<&str>,
+    ) -> Option<&'m V> {
+        locale
+            .into_iter()
+            .chain([self.locale.as_str()])
+            .flat_map(strings::candidates)
+            .find_map(|locale| catalog.get(&locale))
+    }
+
+    /// Fills in the localized defaults of the email and branding, translates catalog messages
+    /// and renders markdown texts. Returns the localized greeting and callout labels as well
+    fn prepare<'a>(
+        &self,
+        email: &Email<'a>,
+    ) -> (
+        Email<'a>,
+        Branding,
+        Option<String>,
+        HashMap<Severity, String>,
+    ) {
+        let strings = self
+            .lookup(&self.strings, email.locale.as_deref())
+            .unwrap_or(&self.strings["en"]);
+        #[cfg(feature = "fluent")]
+        let bundle = self.lookup(&self.bundles, email.locale.as_deref());
+
+        let translate = |text: Text<'a>| -> Text<'a> {
+            #[cfg(feature = "fluent")]
+            let text = match bundle {
+                Some(bundle) => catalog::translate(bundle, text),
+                None => text,
+            };
+            text
+        };
+        let resolve = |text: Text<'a>| -> Text<'a> {
+            let text = translate(text);
+            #[cfg(feature = "markdown")]
+            let text = markdown::render(text);
+            text
+        };
+        // for attributes and metadata, where markup can't be rendered
+        let plain =
+            |text: Text<'a>| -> Text<'a> { Text::Plain(translate(text).to_string().into()) };
+
+        let mut email = email.clone();
+        // used as plain text (subject line, inbox preview), so markdown isn't rendered
+        email.subject = email.subject.map(translate);
+        email.preheader = email.preheader.map(translate);
+        email.summary = email.summary.map(translate);
+        email.hero = email.hero.map(|hero| Image {
+            alt: plain(hero.alt),
+            ..hero
+        });
+        email.go_to_action = email.go_to_action.map(|action| GoToAction {
+            text: plain(action.text),
+            description: plain(action.description),
+            ..action
+        });
+        email.blocks = email
+            .blocks
+            .into_iter()
+            .map(|block| match block {
+                Block::Paragraph { text } => Block::Paragraph {
+                    text: resolve(text),
+                },
+                Block::Dictionary { entries } => Block::Dictionary {
+                    entries: entries
+                        .into_iter()
+                        .map(|(key, value)| (resolve(key), resolve(value)))
+                        .collect(),
+                },
+                Block::Table(table) => Block::Table(Table {
+                    title: resolve(table.title),
+                    columns: table
+                        .columns
+                        .into_iter()
+                        .map(|column| Column {
+                            label: resolve(column.label),
+                            ..column
+                        })
+                        .collect(),
+                    rows: resolve_rows(table.rows, &resolve),
+                    footer: resolve_rows(table.footer, &resolve),
+                }),
+                Block::Action(action) => Block::Action(Action {
+                    text: resolve(action.text),
+                    instructions: action.instructions.map(resolve),
+                    ..action
+                }),
+                Block::Code(code) => Block::Code(VerificationCode {
+                    expiry: code.expiry.map(resolve),
+                    ..code
+                }),
+                Block::Callout(callout) => Block::Callout(Callout {
+                    severity: callout.severity,
+                    title: callout.title.map(resolve),
+                    text: resolve(callout.text),
+                }),
+                Block::List(list) => Block::List(List {
+                    ordered: list.ordered,
+                    items: list
+                        .items
+                        .into_iter()
+                        .map(|item| ListItem {
+                            text: resolve(item.text),
+                            children: item.children.into_iter().map(resolve).collect(),
+                        })
+                        .collect(),
+                }),
+                Block::Image(image) => Block::Image(Image {
+                    alt: plain(image.alt),
+                    ..image
+                }),
+            })
+            .collect();
+
+        let greeting = email.greeting.as_ref().map(|greeting| match greeting {
+            Greeting::Default => strings.greeting.clone(),
+            Greeting::Name(name) => strings.greeting_name.replace("{name}", name),
+            // rendered as plain text in the heading
+            Greeting::Custom(custom) => translate(custom.clone()).to_string(),
+        });
+        email.signature = match email.signature {
+            None => Some(Text::Plain(strings.signature.clone().into())),
+            Some(signature) if signature.to_string().is_empty() => None,
+            signature => signature.map(resolve),
+        };
+
+        let mut branding = self.branding.clone();
+        branding.copyright = match branding.copyright {
+            None => Some(strings.copyright.replace("{name}", &branding.name)),
+            Some(copyright) if copyright.is_empty() => None,
+            copyright => copyright,
+        };
+        branding
+            .trouble_text
+            .get_or_insert_with(|| strings.trouble_text.clone());
+
+        let severities = HashMap::from([
+            (Severity::Info, strings.info.clone()),
+            (Severity::Success, strings.success.clone()),
+            (Severity::Warning, strings.warning.clone()),
+            (Severity::Danger, strings.danger.clone()),
+        ]);
+
+        (email, branding, greeting, severities)
+    }
+
+    /// Subject line of the email in its locale
+    #[cfg(any(feature = "message", feature = "lettre"))]
+    fn subject_line(&self, email: &Email) -> Option<String> {
+        let (email, ..) = self.prepare(email);
+        email.subject_line().map(ToString::to_string)
+    }
+
+    /// Renders a complete MIME message (`multipart/alternative` with the text and HTML version)
+    /// that can be handed to any mail transport as is.
+    ///
+    /// Inline images of the mailgen and of the headers are embedded into the message and their
+    /// references in the HTML are rewritten to `cid:` urls.
+    #[cfg(feature = "message")]
+    pub fn render_message(
+        &self,
+        email: &Email,
+        headers: &message::MessageHeaders,
+    ) -> Result<String, T::Error> {
+        use message::Part;
+
+        let text = self.render_text(email)?;
+        let html = self.render_html(email)?;
+
+        let body = Part::Multipart {
+            subtype: "alternative",
+            parts: vec![
+                Part::text("plain", &text, headers.transfer_encoding),
+                Part::html(
+                    &html,
+                    self.inline_images.iter().chain(&headers.inline_images),
+                    headers,
+                ),
+            ],
+        };
+
+        let subject = self.subject_line(email);
+        Ok(message::build(headers, subject.as_deref(), body))
+    }
+
+    /// Renders the text and HTML version as a `multipart/alternative` body for [`lettre`]
+    ///
+    /// Inline images of the mailgen are embedded like in [`Mailgen::render_message`], the HTML
+    /// version is then wrapped in a `multipart/related` part with the images.
+    #[cfg(feature = "lettre")]
+    pub fn render_multipart(&self, email: &Email) -> Result<lettre::message::MultiPart, T::Error> {
+        use lettre::message::header::ContentType;
+        use lettre::message::{Attachment, MultiPart, SinglePart};
+
+        let text = self.render_text(email)?;
+        let mut html = self.render_html(email)?;
+
+        // images lettre can't attach keep their original reference
+        let images = self
+            .inline_images
+            .iter()
+            .filter(|image| ContentType::parse(&image.content_type).is_ok());
+        let domain = message::host(&self.branding.link);
+        let images = message::embed(&mut html, images, domain);
+        if images.is_empty() {
+            return Ok(MultiPart::alternative_plain_html(text, html));
+        }
+
+        let mut related = MultiPart::related().singlepart(SinglePart::html(html));
+        for (content_id, image) in images {
+            let Ok(content_type) = ContentType::parse(&image.content_type) else {
+                continue;
+            };
+            let attachment = match &image.filename {
+                Some(filename) => Attachment::new_inline_with_name(content_id, filename.clone()),
+                None => Attachment::new_inline(content_id),
+            };
+            related = related.singlepart(attachment.body(image.data.clone(), content_type));
+        }
+
+        Ok(MultiPart::alternative()
+            .singlepart(SinglePart::plain(text))
+            .multipart(related))
+    }
+
+    /// Renders the email into a [`lettre::Message`] using the given builder for the headers.
+    ///
+    /// The subject set on the builder takes precedence, otherwise the subject (or summary) of the email is used.
+    #[cfg(feature = "lettre")]
+    pub fn render_lettre_message(
+        &self,
+        email: &Email,
+        builder: lettre::message::MessageBuilder,
+    ) -> Result<lettre::Message, LettreError<T::Error>> {
+        use lettre::message::header::Subject;
+
+        let body = self.render_multipart(email).map_err(LettreError::Render)?;
+        let mut message = builder.multipart(body)?;
+
+        if message.headers().get::<Subject>().is_none() {
+            if let Some(subject) = self.subject_line(email) {
+                message.headers_mut().set(Subject::from(subject));
+            }
+        }
+
+        Ok(message)
+    }
+}
+
+fn resolve_rows<'a>(
+    rows: Vec<Vec<Cell<'a>>>,
+    resolve: &impl Fn(Text<'a>) -> Text<'a>,
+) -> Vec<Vec<Cell<'a>>> {
+    rows.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|cell| Cell {
+                    text: resolve(cell.text),
+                    ..cell
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Error of [`Mailgen::render_lettre_message`]
+#[cfg(feature = "lettre")]
+#[derive(Debug, thiserror::Error)]
+pub enum LettreError<E: std::error::Error> {
+    #[error("rendering error: {0}")]
+    Render(E),
+    #[error("building message failed: {0}")]
+    Build(#[from] lettre::error::Error),
 }
 
 /// Product represents your company product (brand)
@@ -90,32 +457,35 @@ pub struct Branding {
     pub name: String,
     pub link: String,
     pub logo: Option<String>,
+    /// Logo shown instead of `logo` when the email client is in dark mode
+    pub logo_dark: Option<String>,
+    /// Copyright notice in the footer, defaults to the one of the locale. An empty notice is omitted
     pub copyright: Option<String>,
-    pub trouble_text: String,
+    /// Text displayed above the link of an action in case the button doesn't work,
+    /// `{ACTION}` is replaced with the text of the action. Defaults to the one of the locale
+    pub trouble_text: Option<String>,
 }
 
 impl Branding {
     pub fn new<S: Into<String>>(name: S, link: S) -> Self {
-        let name = name.into();
-        let link = link.into();
-        let copyright = format!("Copyright © {name}. All rights reserved.");
-        let trouble_text = "If you're having trouble with the button '{ACTION}', copy and paste the URL below into your web browser."
-            .to_string();
-
         Self {
-            name,
-            link,
-            trouble_text,
-            copyright: Some(copyright),
+            name: name.into(),
+            link: link.into(),
+            trouble_text: None,
+            copyright: None,
             logo: None,
+            logo_dark: None,
         }
     }
 }
 
-#[cfg(test)]
+// every test renders with the default theme
+#[cfg(all(test, feature = "default-theme"))]
 mod tests {
     use crate::builder::EmailBuilder;
-    use crate::{Action, Branding, Greeting, Mailgen, Table, TableColumns};
+    use crate::{
+        Action, Alignment, Block, Branding, Cell, Column, Direction, Greeting, Mailgen, Table, Text,
+    };
 
     #[test]
     #[cfg(feature = "default-theme")]
@@ -128,23 +498,16 @@ mod tests {
 
         let email = EmailBuilder::new()
             .summary("this is a test email that contains stuff to test...")
-            .greeting(Greeting::Name("person name"))
+            .greeting(Greeting::Name("person name".into()))
             .intro("test intro")
             .intro("another intro")
             .dictionary("test key", "test value")
             .dictionary("test key 2", "test value 2")
-            .action(Action {
-                text: "Test Action",
-                link: "https://test.com/action",
-                color: Some(("black", "white")),
-                ..Default::default()
-            })
-            .action(Action {
-                text: "Test Action 2",
-                link: "https://test.com/action2",
-                instructions: Some("test instruction"),
-                ..Default::default()
-            })
+            .action(Action::new("Test Action", "https://test.com/action").color("black", "white"))
+            .action(
+                Action::new("Test Action 2", "https://test.com/action2")
+                    .instructions("test instruction"),
+            )
             .outro("test outro 1")
             .outro("test outro 2")
             .signature("test signature...")
@@ -163,41 +526,33 @@ mod tests {
     #[cfg(feature = "default-theme")]
     fn test_tables() -> Result<(), Box<dyn std::error::Error>> {
         use crate::themes::DefaultTheme;
-        use std::collections::HashMap;
 
         let theme = DefaultTheme::new()?;
         let branding = Branding::new("test product", "https://testproduct.com");
         let mailgen = Mailgen::new(theme, branding);
 
         // Create table data
-        let mut row1 = HashMap::new();
-        row1.insert("Item", "Product 1");
-        row1.insert("Price", "$10.99");
-        row1.insert("Quantity", "1");
-
-        let mut row2 = HashMap::new();
-        row2.insert("Item", "Product 2");
-        row2.insert("Price", "$24.99");
-        row2.insert("Quantity", "2");
-
-        let mut custom_alignment = HashMap::new();
-        custom_alignment.insert("Price", "right");
-        custom_alignment.insert("Quantity", "center");
-
-        let columns = TableColumns {
-            custom_width: None,
-            custom_alignment: Some(custom_alignment),
-        };
-
-        let table = Table {
-            title: "Order Summary",
-            data: vec![row1, row2],
-            columns: Some(columns),
-        };
+        let table = Table::new("Order Summary")
+            .column(Column::new("Item").width("50%"))
+            .column(Column::new("Quantity").align(Alignment::Center))
+            .column(Column::new("Price").align(Alignment::Right))
+            .row(["Product 1", "1", "$10.99"])
+            .row(["Product 2", "2", "$24.99"])
+            .row(["Product 3", "3"])
+            .row([
+                Cell::new("Product 4").link("https://example.com/product-4"),
+                Cell::new("1"),
+                Cell::new("Free").muted(),
+            ])
+            .footer_row([Cell::new("Subtotal").colspan(2), Cell::new("$60.97")])
+            .footer_row([
+                Cell::new("Total").colspan(2).bold(),
+                Cell::new("$65.85").bold(),
+            ]);
 
         let email = EmailBuilder::new()
             .summary("Order Confirmation")
-            .greeting(Greeting::Name("Customer"))
+            .greeting(Greeting::Name("Customer".into()))
             .intro("Thank you for your order!")
             .table(table)
             .outro("Your order will be processed soon.")
@@ -205,21 +560,35 @@ mod tests {
             .build();
 
         let rendered = mailgen.render_html(&email)?;
+        let item = rendered.find(">Item</p>").unwrap();
+        let quantity = rendered.find(">Quantity</p>").unwrap();
+        let price = rendered.find(">Price</p>").unwrap();
+        assert!(item < quantity && quantity < price);
+        assert!(rendered.find("Product 1").unwrap() < rendered.find("$10.99").unwrap());
+        assert!(rendered.contains("class=\"data-table_bold\" colspan=\"2\""));
+        assert!(rendered.contains("href=\"https://example.com/product-4\""));
+        assert!(rendered.find("Free").unwrap() < rendered.find("Subtotal").unwrap());
         std::fs::write("./email_with_table.html", rendered)?;
 
         let rendered = mailgen.render_text(&email)?;
+        assert!(
+            rendered.contains("Product 1                                 |    1     | $10.99\n")
+        );
+        assert!(
+            rendered.contains("Subtotal                                             | $60.97\n")
+        );
         std::fs::write("./email_with_table.txt", rendered)?;
 
         Ok(())
     }
 
     #[test]
-    #[cfg(feature = "default-theme")]
+    #[cfg(all(feature = "default-theme", feature = "message"))]
     fn create_eml_file() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::message::{Mailbox, MessageHeaders, TransferEncoding};
         use crate::themes::DefaultTheme;
+        use crate::TableColumns;
         use std::collections::HashMap;
-        use std::fs::File;
-        use std::io::Write;
 
         let theme = DefaultTheme::new()?;
         let product = Branding::new("test product", "https://testproduct.com");
@@ -245,72 +614,54 @@ mod tests {
             custom_alignment: Some(custom_alignment),
         };
 
-        let table = Table {
-            title: "Order Summary",
-            data: vec![row1, row2],
-            columns: Some(columns),
-        };
+        let table = Table::from_data("Order Summary", vec![row1, row2], Some(columns));
 
         let email = EmailBuilder::new()
             .summary("Email Test Subject")
-            .greeting(Greeting::Name("Test User"))
+            .greeting(Greeting::Name("Test User".into()))
             .intro("Welcome to our service!")
             .intro("We're excited to have you on board.")
             .dictionary("Account", "test@example.com")
             .dictionary("Plan", "Premium")
-            .action(Action {
-                text: "Confirm Account",
-                link: "https://example.com/confirm",
-                color: Some(("#48cfad", "#ffffff")),
-                ..Default::default()
-            })
+            .action(
+                Action::new("Confirm Account", "https://example.com/confirm")
+                    .color("#48cfad", "#ffffff"),
+            )
             .table(table)
             .outro("Need help, or have questions?")
             .outro("Just reply to this email, we'd love to help.")
             .signature("The Example Team")
             .build();
 
-        let text_content = mailgen.render_text(&email)?;
-        let html_content = mailgen.render_html(&email)?;
+        let headers = MessageHeaders::new(
+            Mailbox::with_name("Test Product", "no-reply@testproduct.com"),
+            Mailbox::with_name("Test User", "test@example.com"),
+        );
+        let message = mailgen.render_message(&email, &headers)?;
 
-        // Create a boundary for the multipart email
-        let boundary = "------------MAILGEN_BOUNDARY";
+        assert!(message.contains("From: \"Test Product\" <no-reply@testproduct.com>\r\n"));
+        assert!(message.contains("To: \"Test User\" <test@example.com>\r\n"));
+        assert!(message.contains("Subject: Email Test Subject\r\n"));
+        assert!(message.contains("\r\nDate: "));
+        assert!(message.contains("@testproduct.com>\r\n"));
+        assert!(message.contains("Content-Type: multipart/alternative;\r\n boundary=\"mailgen-"));
+        assert!(message.contains("Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: quoted-printable\r\n"));
+        assert!(message.contains("Content-Type: text/html; charset=utf-8\r\nContent-Transfer-Encoding: quoted-printable\r\n"));
+        assert!(message.ends_with("--\r\n"));
+        // RFC 5322 line length limit and 7bit content
+        assert!(message.split("\r\n").all(|line| line.len() <= 998));
+        assert!(message.is_ascii());
+        assert!(!message.replace("\r\n", "").contains('\n'));
 
-        // Create an .eml file with proper headers and MIME structure
-        let mut eml_file = File::create("./email_test.eml")?;
+        std::fs::write("./email_test.eml", &message)?;
 
-        // Write email headers
-        writeln!(
-            eml_file,
-            "From: \"Test Product\" <no-reply@testproduct.com>"
-        )?;
-        writeln!(eml_file, "To: \"Test User\" <test@example.com>")?;
-        writeln!(eml_file, "Subject: Email Test Subject")?;
-        writeln!(eml_file, "MIME-Version: 1.0")?;
-        writeln!(
-            eml_file,
-            "Content-Type: multipart/alternative; boundary=\"{boundary}\""
-        )?;
-        writeln!(eml_file)?;
-
-        // Write the text part
-        writeln!(eml_file, "--{boundary}")?;
-        writeln!(eml_file, "Content-Type: text/plain; charset=UTF-8")?;
-        writeln!(eml_file, "Content-Transfer-Encoding: 8bit")?;
-        writeln!(eml_file)?;
-        writeln!(eml_file, "{}", text_content)?;
-        writeln!(eml_file)?;
-
-        // Write the HTML part
-        writeln!(eml_file, "--{boundary}")?;
-        writeln!(eml_file, "Content-Type: text/html; charset=UTF-8")?;
-        writeln!(eml_file, "Content-Transfer-Encoding: 8bit")?;
-        writeln!(eml_file)?;
-        writeln!(eml_file, "{}", html_content)?;
-        writeln!(eml_file)?;
-
-        // End the MIME message
-        writeln!(eml_file, "--{boundary}--")?;
+        let headers = headers
+            .subject("Grüße aus Zürich")
+            .transfer_encoding(TransferEncoding::Base64);
+        let message = mailgen.render_message(&email, &headers)?;
+        assert!(message.contains("Subject: =?utf-8?b?"));
+        assert!(message.contains("Content-Transfer-Encoding: base64\r\n"));
+        assert!(message.is_ascii());
 
         Ok(())
     }
@@ -326,14 +677,12 @@ mod tests {
 
         let email = EmailBuilder::new()
             .summary("Account Activation")
-            .greeting(Greeting::Name("John Doe"))
+            .greeting(Greeting::Name("John Doe".into()))
             .intro("Welcome to our service! Please activate your account.")
-            .action(Action {
-                text: "Activate Account",
-                link: "https://example.com/activate",
-                instructions: Some("Click the button below to activate your account:"),
-                ..Default::default()
-            })
+            .action(
+                Action::new("Activate Account", "https://example.com/activate")
+                    .instructions("Click the button below to activate your account:"),
+            )
             .go_to_action(
                 "Activate Now",
                 "https://example.com/activate",
@@ -355,4 +704,1294 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_escaping() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("Test <Company>", "https://example.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .summary("<title>")
+            .greeting(Greeting::Name("<script>alert(1)</script>".into()))
+            .intro("Your note: <b>\"quoted\"</b>")
+            .intro(Text::Html("<b>trusted</b>".into()))
+            .dictionary("Name", "<img src=x onerror=alert(1)>")
+            .table(
+                Table::new("Links")
+                    .column(Column::new("Link"))
+                    .row([Cell::new("Click").link("javascript:alert(1)")]),
+            )
+            .action(
+                Action::new("Click", "javascript:alert(1)")
+                    .color("red;background:url(x)", "\"><script>"),
+            )
+            .go_to_action(
+                "Open",
+                "https://example.com/?a=1&b=2",
+                "Line 1\nLine 2 \"quoted\" </script><script>",
+            )
+            .build();
+
+        let rendered = mailgen.render_html(&email)?;
+        assert!(!rendered.contains("<script>alert(1)</script>"));
+        assert!(rendered.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(rendered.contains("&lt;b&gt;"));
+        assert!(rendered.contains(">trusted</b>"));
+        assert!(!rendered.contains("<img src=x"));
+        assert!(!rendered.contains("href=\"javascript:"));
+        assert!(!rendered.contains("background:url"));
+        assert!(!rendered.contains("</script><script>"));
+        assert!(rendered.contains("Test &lt;Company&gt;"));
+
+        let rendered = mailgen.render_text(&email)?;
+        assert!(rendered.contains("<script>alert(1)</script>"));
+        assert!(rendered.contains("<img src=x onerror=alert(1)>"));
+        assert!(rendered.contains("Click (#)"));
+        assert!(!rendered.contains("javascript:"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_go_to_action_json_ld() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::markup::{Order, Organization};
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("Test Company", "https://example.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let description = "Say \"hi\"\nand </script><script>alert(1)</script>";
+        let email = EmailBuilder::new()
+            .go_to_action("Open \\ now", "https://example.com/?a=1&b=2", description)
+            .build();
+
+        let rendered = mailgen.render_html(&email)?;
+        let script = &rendered[rendered.find("application/ld+json").unwrap()..];
+        let start = script.find('>').unwrap() + 1;
+        let end = script.find("</script>").unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&script[start..end])?;
+        assert_eq!(json["@type"], "EmailMessage");
+        assert_eq!(json["description"], description);
+        assert_eq!(json["potentialAction"]["name"], "Open \\ now");
+        assert_eq!(
+            json["potentialAction"]["url"],
+            "https://example.com/?a=1&b=2"
+        );
+
+        // links of the markup are sanitized like links of the email
+        let email = EmailBuilder::new()
+            .go_to_action("Open", "javascript:alert(1)", "Open it")
+            .save_action("Save", " JavaScript:alert(2)", "Save it")
+            .order(Order {
+                url: Some("javascript:alert(3)".into()),
+                ..Order::new(Organization::new("Test Shop"), "123-456")
+            })
+            .build();
+        let json_ld = email.json_ld()?.unwrap();
+        assert!(!json_ld.to_lowercase().contains("javascript:"));
+        let json: serde_json::Value = serde_json::from_str(&json_ld)?;
+        assert_eq!(json[0]["potentialAction"]["url"], "#");
+        assert_eq!(json[1]["potentialAction"]["handler"]["url"], "#");
+        assert_eq!(json[2]["url"], "#");
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_markup() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::markup::{
+            Event, EventReservation, Invoice, Offer, Order, OrderStatus, Organization,
+            ParcelDelivery, PaymentStatus, Person, Place, PostalAddress, PriceSpecification,
+            Product, ReservationStatus,
+        };
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("Test Shop", "https://example.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let order = Order {
+            order_status: Some(OrderStatus::Processing),
+            price: Some("29.99".into()),
+            price_currency: Some("USD".into()),
+            accepted_offer: vec![Offer {
+                item_offered: Product::new("Widget"),
+                price: "29.99".into(),
+                price_currency: "USD".into(),
+            }],
+            ..Order::new(Organization::new("Test Shop"), "123-456")
+        };
+
+        let email = EmailBuilder::new()
+            .summary("Your order")
+            .confirm_action("Approve", "https://example.com/approve", "Approve order")
+            .order(order.clone())
+            .parcel_delivery(ParcelDelivery {
+                carrier: Some(Organization::new("FedEx")),
+                tracking_number: Some("TRACK123".into()),
+                ..ParcelDelivery::new(order)
+            })
+            .event_reservation(EventReservation {
+                reservation_number: "R-1".into(),
+                reservation_status: ReservationStatus::Confirmed,
+                under_name: Person {
+                    name: "Jane Doe".into(),
+                    email: None,
+                },
+                reservation_for: Event {
+                    name: "Conference".into(),
+                    start_date: "2026-05-15T08:30:00-08:00".into(),
+                    end_date: None,
+                    location: Place {
+                        name: "Convention Center".into(),
+                        address: PostalAddress {
+                            address_locality: Some("San Francisco".into()),
+                            ..Default::default()
+                        },
+                    },
+                },
+                url: None,
+            })
+            .invoice(Invoice {
+                payment_status: Some(PaymentStatus::Due),
+                total_payment_due: Some(PriceSpecification {
+                    price: "70.00".into(),
+                    price_currency: Some("USD".into()),
+                }),
+                ..Invoice::new(Organization::new("Test Shop"))
+            })
+            .build();
+
+        let json: serde_json::Value = serde_json::from_str(&email.json_ld()?.unwrap())?;
+        let items = json.as_array().unwrap();
+        assert_eq!(items.len(), 5);
+        assert!(items
+            .iter()
+            .all(|item| item["@context"] == "http://schema.org"));
+
+        assert_eq!(items[0]["@type"], "EmailMessage");
+        assert_eq!(items[0]["description"], "Approve order");
+        assert_eq!(items[0]["potentialAction"]["@type"], "ConfirmAction");
+        assert_eq!(
+            items[0]["potentialAction"]["handler"]["url"],
+            "https://example.com/approve"
+        );
+
+        assert_eq!(items[1]["@type"], "Order");
+        assert_eq!(items[1]["merchant"]["@type"], "Organization");
+        assert_eq!(items[1]["orderStatus"], "http://schema.org/OrderProcessing");
+        assert_eq!(
+            items[1]["acceptedOffer"][0]["itemOffered"]["name"],
+            "Widget"
+        );
+
+        assert_eq!(items[2]["@type"], "ParcelDelivery");
+        assert_eq!(items[2]["partOfOrder"]["orderNumber"], "123-456");
+        assert_eq!(items[3]["@type"], "EventReservation");
+        assert_eq!(
+            items[3]["reservationFor"]["location"]["address"]["@type"],
+            "PostalAddress"
+        );
+        assert_eq!(items[4]["@type"], "Invoice");
+        assert_eq!(items[4]["paymentStatus"], "http://schema.org/PaymentDue");
+
+        let rendered = mailgen.render_html(&email)?;
+        assert!(rendered.contains("\"@type\":\"EventReservation\""));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_text_tables() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let description = "A rather long product description that does not fit into a single line of the text version";
+        let wrapped = Table::new("Wrapped")
+            .column(Column::new("Item"))
+            .column(Column::new("Description"))
+            .column(Column::new("Price").align(Alignment::Right))
+            .row(["Product 1", description, "$10.99"]);
+
+        let wide = Table::new("Stacked")
+            .column(Column::new("First"))
+            .column(Column::new("Second"))
+            .column(Column::new("Third"))
+            .row([
+                "Supercalifragilisticexpialidocious",
+                "Pneumonoultramicroscopicsilicovolcanoconiosis",
+                "Hippopotomonstrosesquippedaliophobia",
+            ])
+            .footer_row(["Total", "3"]);
+
+        let email = EmailBuilder::new().table(wrapped).table(wide).build();
+        let rendered = mailgen.render_text(&email)?;
+
+        assert!(rendered.lines().all(|line| line.chars().count() <= 80));
+        assert!(rendered.contains(
+            "Product 1 | A rather long product description that does not fit into a  | $10.99\n"
+        ));
+        assert!(rendered.contains("          | single line of the text version "));
+
+        assert!(rendered.contains("First: Supercalifragilisticexpialidocious\n"));
+        assert!(rendered.contains("Second: Pneumonoultramicroscopicsilicovolcanoconiosis\n"));
+        assert!(rendered.contains("Total: 3"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_deserialize() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+        use crate::Email;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("Test Shop", "https://example.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let payload = String::from(
+            r##"{
+            "summary": "Order \"42\" shipped",
+            "greeting": { "name": "Jane" },
+            "intros": ["Your order is on its way.", { "html": "<b>Thanks!</b>" }],
+            "dictionary": [["Order", "42"]],
+            "tables": [{
+                "title": "Items",
+                "columns": ["Item", { "key": "price", "label": "Price", "align": "right" }],
+                "rows": [["Widget", "$10.00"]],
+                "footer": [[{ "text": "Total", "emphasis": "bold" }, "$10.00"]]
+            }],
+            "actions": [{ "text": "Track", "link": "https://example.com/track", "color": ["#fff", "#000"] }],
+            "blocks": [{ "type": "paragraph", "text": "Questions? Just reply." }],
+            "markup": [{
+                "@type": "SaveAction",
+                "name": "Save",
+                "handler": { "url": "https://example.com/save" },
+                "description": "Save the order"
+            }]
+        }"##,
+        );
+
+        let email: Email<'static> = serde_json::from_str(&payload)?;
+        drop(payload);
+
+        assert_eq!(
+            email.summary,
+            Some(Text::Plain("Order \"42\" shipped".into()))
+        );
+        assert!(matches!(
+            email.blocks[1],
+            Block::Paragraph {
+                text: Text::Html(_)
+            }
+        ));
+        let Block::Table(table) = &email.blocks[3] else {
+            panic!("expected a table block");
+        };
+        assert_eq!(table.columns[0].label, Text::from("Item"));
+        assert_eq!(table.columns[1].align, Alignment::Right);
+        assert!(matches!(
+            email.markup.as_ref().unwrap()[0],
+            crate::markup::Markup::SaveAction(_)
+        ));
+
+        let rendered = mailgen.render_html(&email)?;
+        assert!(rendered.contains("Hey Jane,"));
+        assert!(rendered.contains("\"@type\":\"SaveAction\""));
+
+        let rendered = mailgen.render_text(&email)?;
+        assert!(rendered.contains("Widget | $10.00"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_serialize_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::markup::ViewAction;
+        use crate::themes::DefaultTheme;
+        use crate::{Callout, CatalogMessage, Email, Image, List, VerificationCode};
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .summary("Welcome")
+            .hero(Image::new("https://testproduct.com/hero.png", "Hero"))
+            .greeting(Greeting::Name("Jane".into()))
+            .intro("plain <intro>")
+            .intro(Text::Html("<b>html</b>".into()))
+            .intro(Text::Markdown("**markdown**".into()))
+            .intro(
+                CatalogMessage::new("welcome")
+                    .arg("name", "Jane")
+                    .arg("items", 3),
+            )
+            .dictionary("Plan", "Premium")
+            .table(
+                Table::new("Items")
+                    .column(Column::new("item"))
+                    .row([Cell::new("Widget").link("https://testproduct.com/widget")]),
+            )
+            .action(Action::new("Confirm", "https://testproduct.com/confirm"))
+            .code(VerificationCode::new("482913").expiry("Expires soon"))
+            .callout(Callout::warning("Careful").title("Note"))
+            .list(List::ordered().item_with_children("One", vec!["Two"]))
+            .image(Image::new("https://testproduct.com/product.png", "Product").width(200))
+            .outro("Bye")
+            .view_action("View", "https://testproduct.com/view", "View it")
+            .direction(Direction::Rtl)
+            .locale("de")
+            .build();
+
+        let json = serde_json::to_string(&email)?;
+        let deserialized: Email<'static> = serde_json::from_str(&json)?;
+        assert_eq!(serde_json::to_string(&deserialized)?, json);
+
+        assert!(matches!(deserialized.greeting, Some(Greeting::Name(_))));
+        let texts: Vec<_> = deserialized.blocks[..4]
+            .iter()
+            .map(|block| match block {
+                Block::Paragraph { text } => text.clone(),
+                _ => panic!("expected a paragraph"),
+            })
+            .collect();
+        assert_eq!(texts[0], Text::Plain("plain <intro>".into()));
+        assert_eq!(texts[1], Text::Html("<b>html</b>".into()));
+        assert_eq!(texts[2], Text::Markdown("**markdown**".into()));
+        assert_eq!(
+            texts[3],
+            CatalogMessage::new("welcome")
+                .arg("name", "Jane")
+                .arg("items", 3)
+                .into()
+        );
+        assert!(matches!(
+            deserialized.markup.as_deref(),
+            Some([crate::markup::Markup::ViewAction(ViewAction { .. })])
+        ));
+
+        assert_eq!(
+            mailgen.render_html(&deserialized)?,
+            mailgen.render_html(&email)?
+        );
+        assert_eq!(
+            mailgen.render_text(&deserialized)?,
+            mailgen.render_text(&email)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "default-theme", feature = "message"))]
+    fn test_inline_images() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::message::{InlineImage, Mailbox, MessageHeaders};
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let mut branding = Branding::new("Test Company", "https://example.com");
+        branding.logo = Some("https://example.com/logo.png?size=2&dark=1".to_string());
+
+        let logo = InlineImage::new(
+            "https://example.com/logo.png?size=2&dark=1",
+            "image/png",
+            b"logo".to_vec(),
+        )
+        .filename("logo.png");
+        let mailgen = Mailgen::new(theme, branding).inline_image(logo);
+
+        let email = EmailBuilder::new()
+            .summary("Inline images")
+            .intro(Text::Html("<img src=\"banner.jpg\" alt=\"\">".into()))
+            .build();
+
+        let headers = MessageHeaders::new(
+            Mailbox::new("no-reply@example.com"),
+            Mailbox::new("user@example.com"),
+        )
+        .inline_image(InlineImage::new(
+            "banner.jpg",
+            "image/jpeg",
+            b"banner".to_vec(),
+        ))
+        .inline_image(InlineImage::new(
+            "unused.gif",
+            "image/gif",
+            b"unused".to_vec(),
+        ));
+
+        let message = mailgen.render_message(&email, &headers)?;
+        assert!(message.contains("multipart/related;"));
+        assert!(message.contains("Content-Type: image/png\r\n"));
+        assert!(message.contains("Content-Type: image/jpeg\r\n"));
+        assert!(!message.contains("image/gif"));
+        assert!(message.contains("Content-Disposition: inline; filename=\"logo.png\"\r\n"));
+        // "logo" and "banner" in base64
+        assert!(message.contains("\r\nbG9nbw==\r\n"));
+        assert!(message.contains("\r\nYmFubmVy\r\n"));
+        assert!(!message.contains("logo.png?size"));
+        assert!(!message.contains("banner.jpg"));
+
+        let content_ids = message.matches("Content-ID: <").count();
+        assert_eq!(content_ids, 2);
+        // quoted-printable encodes `=` as `=3D`
+        assert_eq!(message.matches("src=3D\"cid:").count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "default-theme", feature = "lettre"))]
+    fn test_lettre() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::message::InlineImage;
+        use crate::themes::DefaultTheme;
+        use lettre::transport::stub::StubTransport;
+        use lettre::{Message, Transport};
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .summary("Your order has shipped")
+            .greeting(Greeting::Name("Jane".into()))
+            .intro("Your package is on its way.")
+            .build();
+
+        let builder = Message::builder()
+            .from("Test Product <no-reply@testproduct.com>".parse()?)
+            .to("jane@example.com".parse()?);
+        let message = mailgen.render_lettre_message(&email, builder)?;
+
+        let transport = StubTransport::new_ok();
+        transport.send(&message)?;
+
+        let messages = transport.messages();
+        assert_eq!(messages.len(), 1);
+        let (envelope, raw) = &messages[0];
+        assert_eq!(envelope.to()[0].to_string(), "jane@example.com");
+        assert!(raw.contains("Subject: Your order has shipped\r\n"));
+        assert!(raw.contains("Content-Type: multipart/alternative;"));
+        assert!(raw.contains("Content-Type: text/plain; charset=utf-8\r\n"));
+        assert!(raw.contains("Content-Type: text/html; charset=utf-8\r\n"));
+        assert!(raw.contains("Hey Jane,"));
+
+        // an explicit subject takes precedence over the summary
+        let builder = Message::builder()
+            .from("no-reply@testproduct.com".parse()?)
+            .to("jane@example.com".parse()?)
+            .subject("Shipping confirmation");
+        let message = mailgen.render_lettre_message(&email, builder)?;
+        let raw = String::from_utf8(message.formatted())?;
+        assert!(raw.contains("Subject: Shipping confirmation\r\n"));
+        assert!(!raw.contains("Subject: Your order has shipped"));
+
+        // inline images are embedded into a multipart/related part
+        let mut branding = Branding::new("test product", "https://testproduct.com");
+        branding.logo = Some("https://testproduct.com/logo.png".to_string());
+        let mailgen = Mailgen::new(DefaultTheme::new()?, branding)
+            .inline_image(
+                InlineImage::new(
+                    "https://testproduct.com/logo.png",
+                    "image/png",
+                    b"logo".to_vec(),
+                )
+                .filename("logo.png"),
+            )
+            .inline_image(InlineImage::new(
+                "unused.gif",
+                "image/gif",
+                b"unused".to_vec(),
+            ));
+        let builder = Message::builder()
+            .from("no-reply@testproduct.com".parse()?)
+            .to("jane@example.com".parse()?);
+        let message = mailgen.render_lettre_message(&email, builder)?;
+        let raw = String::from_utf8(message.formatted())?;
+        assert!(raw.contains("Content-Type: multipart/alternative;"));
+        assert!(raw.contains("Content-Type: multipart/related;"));
+        assert!(raw.contains("Content-Type: image/png\r\n"));
+        assert!(raw.contains("Content-ID: <"));
+        assert!(raw.contains("@testproduct.com>\r\n"));
+        assert!(raw.contains("Content-Disposition: inline; filename=\"logo.png\"\r\n"));
+        assert!(!raw.contains("image/gif"));
+        assert!(!raw.contains("https://testproduct.com/logo.png"));
+        assert!(raw.contains("cid:"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_subject_preheader() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .subject("Your receipt")
+            .preheader("Thanks for your purchase of $42")
+            .summary("Receipt for order #42")
+            .build();
+        assert_eq!(email.subject_line(), Some(&"Your receipt".into()));
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains(">Your receipt</title>"));
+        assert!(html.contains(">Thanks for your purchase of $42\u{34f}"));
+        assert!(!html.contains("Receipt for order #42"));
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("Receipt for order #42"));
+        assert!(!text.contains("Thanks for your purchase"));
+
+        let email = EmailBuilder::new().summary("Receipt for order #42").build();
+        assert_eq!(email.subject_line(), Some(&"Receipt for order #42".into()));
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains(">Receipt for order #42</title>"));
+
+        // the summary becomes the preview text, padded so no body text follows it
+        let start = html
+            .find("class=\"preheader\"")
+            .ok_or("missing preheader")?;
+        let preheader = &html[start..];
+        let preheader = &preheader[..preheader.find("</span>").ok_or("unclosed preheader")?];
+        assert!(preheader.contains(">Receipt for order #42\u{34f}\u{200c}&nbsp;"));
+        assert_eq!(preheader.matches('\u{200c}').count(), 100);
+
+        let email = EmailBuilder::new().build();
+        let html = mailgen.render_html(&email)?;
+        assert!(!html.contains("class=\"preheader\""));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_custom_templates() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let email = EmailBuilder::new()
+            .greeting(Greeting::Name("<Jane>".into()))
+            .intro("Welcome aboard")
+            .build();
+
+        let theme = DefaultTheme::from_templates(
+            "<style>p { color: red; }</style><p>{{ greeting }}</p>",
+            "<p>{{ branding.name }}: {{ email.blocks[0].text | text }}</p>",
+        )?;
+        let mailgen = Mailgen::new(theme, branding.clone());
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("<p style=\"color: red;\">Hey &lt;Jane&gt;,</p>"));
+        let text = mailgen.render_text(&email)?;
+        assert_eq!(text, "test product: Welcome aboard\n");
+
+        let directory =
+            std::env::temp_dir().join(format!("mailgen-templates-{}", std::process::id()));
+        std::fs::create_dir_all(&directory)?;
+        std::fs::write(
+            directory.join("template.html"),
+            "<h1>{{ greeting }}</h1>{% include \"footer.html\" %}",
+        )?;
+        std::fs::write(directory.join("template.text"), "{{ greeting }}")?;
+        std::fs::write(directory.join("footer.html"), "<p>{{ branding.name }}</p>")?;
+
+        // included templates are loaded lazily from the directory
+        let theme = DefaultTheme::from_directory(&directory)?;
+        let mailgen = Mailgen::new(theme, branding);
+        let html = mailgen.render_html(&email);
+        std::fs::remove_dir_all(&directory)?;
+        assert!(html?.contains("<h1>Hey &lt;Jane&gt;,</h1><p>test product</p>"));
+
+        assert!(DefaultTheme::from_directory(&directory).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_template_inheritance() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::from_templates(
+            r#"{% extends "default.html" %}
+{% block styles %}{{ super() }} .custom-footer { color: #123456; }{% endblock %}
+{% block footer %}<tr><td class="custom-footer">Sent with care</td></tr>{% endblock %}"#,
+            r#"{% extends "default.text" %}{% block footer %}<p>Sent with care</p>{% endblock %}"#,
+        )?;
+        let mut branding = Branding::new("test product", "https://testproduct.com");
+        branding.copyright = Some("Copyright notice".to_string());
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .intro("Welcome aboard")
+            .table(
+                Table::new("Order")
+                    .column(Column::new("item").label("Item"))
+                    .row(["Coffee"]),
+            )
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("color: #123456;\">Sent with care</td>"));
+        assert!(html.contains("Welcome aboard"));
+        assert!(html.contains("Coffee"));
+        assert!(!html.contains("Copyright notice"));
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("Welcome aboard"));
+        assert!(text.ends_with("Sent with care\n"));
+        assert!(!text.contains("Copyright notice"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_theme_style() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::{DefaultTheme, ThemeStyle};
+
+        let mut theme = DefaultTheme::new()?;
+        theme.style = ThemeStyle {
+            primary_color: "#ff5a5f".to_string(),
+            button_color: "rgb(0, 128, 0)".to_string(),
+            button_text_color: "#000;} body { display: none".to_string(),
+            font_family: "'Open Sans', sans-serif".to_string(),
+            body_width: 640,
+            border_radius: 8,
+            dark: None,
+            ..ThemeStyle::default()
+        };
+        let mailgen = Mailgen::new(
+            theme,
+            Branding::new("test product", "https://testproduct.com"),
+        );
+
+        let email = EmailBuilder::new()
+            .action(Action::new("Confirm", "https://testproduct.com/confirm"))
+            .outro(Text::Html(
+                "<a href=\"https://testproduct.com\">link</a>".into(),
+            ))
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("font-family: 'Open Sans', sans-serif;"));
+        assert!(html.contains("background-color: rgb(0, 128, 0);border-radius: 8px;"));
+        assert!(html.contains("color: #000 body  display none;"));
+        assert!(html.contains("color: #ff5a5f"));
+        assert!(html.contains("width=\"640\""));
+        assert!(!html.contains("#3869D4"));
+        assert!(!html.contains("570"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_dark_mode() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::{DarkStyle, DefaultTheme, ThemeStyle};
+
+        let mut theme = DefaultTheme::new()?;
+        theme.style.dark = Some(DarkStyle {
+            background_color: "#000000".to_string(),
+            ..DarkStyle::default()
+        });
+        let mut branding = Branding::new("test product", "https://testproduct.com");
+        branding.logo = Some("https://testproduct.com/logo.png".to_string());
+        branding.logo_dark = Some("https://testproduct.com/logo-dark.png".to_string());
+        let mailgen = Mailgen::new(theme, branding.clone());
+
+        let email = EmailBuilder::new()
+            .action(Action::new("Confirm", "https://testproduct.com/confirm"))
+            .action(Action::new("Custom", "https://testproduct.com/custom").color("#000", "#fff"))
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("name=\"color-scheme\" content=\"light dark\""));
+        // the dark mode styles survive css inlining
+        assert!(html.contains("@media (prefers-color-scheme: dark)"));
+        assert!(html.contains("background-color: #000000 !important;"));
+        assert!(html.contains("class=\"email-logo email-logo_light\""));
+        assert!(html.contains("src=\"https://testproduct.com/logo-dark.png\""));
+        assert_eq!(html.matches("class=\"button button_default\"").count(), 1);
+
+        let mut theme = DefaultTheme::new()?;
+        theme.style = ThemeStyle {
+            dark: None,
+            ..ThemeStyle::default()
+        };
+        let mailgen = Mailgen::new(theme, branding);
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("name=\"color-scheme\" content=\"light only\""));
+        assert!(!html.contains("prefers-color-scheme"));
+        assert!(!html.contains("logo-dark.png"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_right_to_left() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding).direction(Direction::Rtl);
+
+        let email = EmailBuilder::new()
+            .greeting(Greeting::Custom("مرحبا".into()))
+            .intro("شكرا لطلبك")
+            .dictionary("الطلب", "42")
+            .table(
+                Table::new("الطلب")
+                    .column(Column::new("item").label("المنتج"))
+                    .column(Column::new("price").label("السعر").align(Alignment::Right))
+                    .row(["قهوة", "$3"]),
+            )
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        // alignments are mirrored
+        let cell = |text: &str, tag: &str| {
+            let end = html.find(text).unwrap_or_default();
+            html[..end]
+                .rsplit(tag)
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+        assert!(cell("المنتج", "<th").contains("text-align: right\""));
+        assert!(cell("السعر", "<th").contains("text-align: left\""));
+        assert!(cell("قهوة", "<td").contains("text-align: right\""));
+        assert!(cell("$3", "<td").contains("text-align: left\""));
+        assert!(html.contains("<body dir=\"rtl\""));
+        assert!(html.contains("margin-right: 0;"));
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text
+            .lines()
+            .filter(|line| !line.is_empty())
+            .all(|line| line.starts_with('\u{200f}')));
+
+        // the direction of the email takes precedence
+        let email = EmailBuilder::new()
+            .intro("Thanks for your order")
+            .direction(Direction::Ltr)
+            .build();
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("<body dir=\"ltr\""));
+        let text = mailgen.render_text(&email)?;
+        assert!(!text.contains('\u{200f}'));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_localization() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+        use crate::Strings;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let italian = Strings {
+            greeting: "Ciao".to_string(),
+            greeting_name: "Ciao {name},".to_string(),
+            signature: "Cordiali saluti,".to_string(),
+            copyright: "Copyright © {name}. Tutti i diritti riservati.".to_string(),
+            trouble_text: "Se il pulsante '{ACTION}' non funziona, copia l'URL nel browser."
+                .to_string(),
+            info: "INFO".to_string(),
+            success: "SUCCESSO".to_string(),
+            warning: "AVVISO".to_string(),
+            danger: "PERICOLO".to_string(),
+        };
+        let mailgen = Mailgen::new(theme, branding)
+            .locale("de")
+            .strings("it", italian);
+
+        // locale of the mailgen
+        let email = EmailBuilder::new()
+            .action(Action::new("Bestätigen", "https://testproduct.com/confirm"))
+            .build();
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("# Hallo\n"));
+        assert!(text.contains("Viele Grüße,"));
+        assert!(text.contains("Copyright © test product. Alle Rechte vorbehalten."));
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("Falls der Button 'Bestätigen' nicht funktioniert"));
+
+        // locale of the email, falling back to the language
+        let email = EmailBuilder::new()
+            .greeting(Greeting::Name("Jeanne".into()))
+            .locale("fr-CA")
+            .build();
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("# Bonjour Jeanne,\n"));
+        assert!(text.contains("Cordialement,"));
+
+        // custom strings, empty signature and explicit greeting
+        let email = EmailBuilder::new()
+            .greeting(Greeting::Custom("Buongiorno".into()))
+            .signature("")
+            .locale("it")
+            .build();
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("# Buongiorno\n"));
+        assert!(!text.contains("Cordiali saluti,"));
+        assert!(text.contains("Tutti i diritti riservati."));
+
+        // unknown locales fall back to the locale of the mailgen
+        let email = EmailBuilder::new().locale("xx").build();
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("# Hallo\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "default-theme", feature = "fluent"))]
+    fn test_fluent() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::fluent_bundle::FluentResource;
+        use crate::themes::DefaultTheme;
+        use crate::{CatalogMessage, Email, FluentBundle, Image};
+
+        let bundle =
+            |locale: &str, source: &str| -> Result<FluentBundle, Box<dyn std::error::Error>> {
+                let mut bundle = FluentBundle::new_concurrent(vec![locale.parse()?]);
+                bundle.set_use_isolating(false);
+                let resource =
+                    FluentResource::try_new(source.to_string()).map_err(|_| "invalid ftl")?;
+                bundle
+                    .add_resource(resource)
+                    .map_err(|_| "duplicate message")?;
+                Ok(bundle)
+            };
+
+        let english = bundle(
+            "en",
+            "shipped = { $items ->\n    [one] Your item is on its way.\n   *[other] Your { $items } items are on their way.\n}\norder = Order { $order }\nnumber = Order number\n",
+        )?;
+        let german = bundle(
+            "de",
+            "shipped = { $items ->\n    [one] Dein Artikel ist unterwegs.\n   *[other] Deine { $items } Artikel sind unterwegs.\n}\norder = Bestellung { $order }\nsubject = Deine Bestellung\ntrack = Sendung verfolgen\nitem = Artikel\nthanks = Danke!\nnumber = Bestellnummer\nproduct = Produkt\nhello = Servus!\nphoto = Produktfoto\nquick = Jetzt verfolgen\nquick-description = Verfolge deine Bestellung\n",
+        )?;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding)
+            .fluent_bundle("en", english)
+            .fluent_bundle("de", german);
+
+        let email = EmailBuilder::new()
+            .intro(CatalogMessage::new("shipped").arg("items", 3))
+            .dictionary(
+                CatalogMessage::new("number"),
+                CatalogMessage::new("order").arg("order", "42"),
+            )
+            .outro(CatalogMessage::new("missing-message"))
+            .build();
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("Your 3 items are on their way."));
+        assert!(text.contains("Order number: Order 42"));
+        assert!(text.contains("missing-message"));
+
+        let mut email = email;
+        email.locale = Some("de-AT".into());
+        email.blocks[0] = Block::paragraph(CatalogMessage::new("shipped").arg("items", 1));
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("Dein Artikel ist unterwegs."));
+        assert!(html.contains(">Bestellnummer:</dt>"));
+        assert!(html.contains("Bestellung 42"));
+
+        // messages can be deserialized as well
+        let email: Email<'static> = serde_json::from_str(
+            r#"{"intros": [{"message": {"id": "shipped", "args": {"items": 2}}}], "locale": "de"}"#,
+        )?;
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("Deine 2 Artikel sind unterwegs."));
+
+        // every text of the email can be a message
+        let email = EmailBuilder::new()
+            .subject(CatalogMessage::new("subject"))
+            .summary(CatalogMessage::new("shipped").arg("items", 1))
+            .greeting(Greeting::Custom(CatalogMessage::new("hello").into()))
+            .image(Image::new(
+                "https://testproduct.com/product.png",
+                CatalogMessage::new("photo"),
+            ))
+            .table(
+                Table::new(CatalogMessage::new("order").arg("order", "42"))
+                    .column(Column::new("item").label(CatalogMessage::new("product")))
+                    .row([CatalogMessage::new("item")]),
+            )
+            .action(
+                Action::new(
+                    CatalogMessage::new("track"),
+                    "https://testproduct.com/track",
+                )
+                .instructions(CatalogMessage::new("shipped").arg("items", 2)),
+            )
+            .signature(CatalogMessage::new("thanks"))
+            .go_to_action(
+                CatalogMessage::new("quick"),
+                "https://testproduct.com/track",
+                CatalogMessage::new("quick-description"),
+            )
+            .locale("de")
+            .build();
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains(">Deine Bestellung</title>"));
+        assert!(html.contains(">Servus!</h1>"));
+        assert!(html.contains("alt=\"Produktfoto\""));
+        assert!(html.contains(">Produkt</p>"));
+        assert!(html.contains("\"name\":\"Jetzt verfolgen\""));
+        assert!(html.contains("\"description\":\"Verfolge deine Bestellung\""));
+        assert!(html.contains(">Bestellung 42</h1>"));
+        assert!(html.contains("Sendung verfolgen\n"));
+        assert!(html.contains(">Deine 2 Artikel sind unterwegs.</p>"));
+        assert!(html.contains("Falls der Button 'Sendung verfolgen' nicht funktioniert"));
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("Dein Artikel ist unterwegs."));
+        assert!(text.contains("Servus!"));
+        assert!(text.contains("[Produktfoto]"));
+        assert!(text.contains("Produkt"));
+        assert!(text.contains("Artikel"));
+        assert!(text.contains("Sendung verfolgen: https://testproduct.com/track"));
+        assert!(text.contains("Danke!"));
+        #[cfg(feature = "message")]
+        {
+            use crate::message::{Mailbox, MessageHeaders};
+
+            let headers = MessageHeaders::new(
+                Mailbox::new("no-reply@testproduct.com"),
+                Mailbox::new("test@example.com"),
+            );
+            let message = mailgen.render_message(&email, &headers)?;
+            assert!(message.contains("Subject: Deine Bestellung\r\n"));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "default-theme", feature = "markdown"))]
+    fn test_markdown() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .intro(Text::Markdown(
+                "Your order is **ready**, see [your account](https://testproduct.com/account).\n\nThanks!".into(),
+            ))
+            .dictionary("Note", Text::Markdown("_fragile_ <script>alert(1)</script>".into()))
+            .outro(Text::Markdown("[click](javascript:alert(1))".into()))
+            .table(
+                Table::new(Text::Markdown("Your _order_".into()))
+                    .column(Column::new("item").label("Item"))
+                    .row([Text::Markdown("**Widget** & [more](https://testproduct.com/more)".into())]),
+            )
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains(">ready</strong>"));
+        assert!(html.contains("<a href=\"https://testproduct.com/account\""));
+        assert!(html.contains("<br><br>Thanks!"));
+        assert!(html.contains(">fragile</em> &lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("**"));
+        assert!(html.contains(">Widget</strong> &amp; <a href=\"https://testproduct.com/more\""));
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("Your order is **ready**, see [your account][1]."));
+        assert!(text.contains("[1]: https://testproduct.com/account"));
+        assert!(text.contains("*fragile* <script>alert(1)</script>"));
+        assert!(!text.contains("javascript:"));
+        assert!(text.contains("Your *order*"));
+        assert!(text.contains("\nWidget & [more]\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_blocks() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let table = Table::new("Items")
+            .column(Column::new("Item"))
+            .row(["Widget"]);
+        let email = EmailBuilder::new()
+            .intro("first paragraph")
+            .action(Action::new("Confirm", "https://testproduct.com/confirm"))
+            .paragraph("second paragraph")
+            .table(table)
+            .dictionary("Order", "42")
+            .dictionary("Plan", "Premium")
+            .outro("last paragraph")
+            .build();
+        assert_eq!(email.blocks.len(), 6);
+
+        let position = |haystack: &str, needle: &str| haystack.find(needle).unwrap();
+        let html = mailgen.render_html(&email)?;
+        let order = [
+            ">first paragraph</p>",
+            "https://testproduct.com/confirm",
+            ">second paragraph</p>",
+            "Widget",
+            ">Order:</dt>",
+            ">Plan:</dt>",
+            ">last paragraph</p>",
+        ];
+        assert!(order
+            .windows(2)
+            .all(|pair| position(&html, pair[0]) < position(&html, pair[1])));
+
+        let text = mailgen.render_text(&email)?;
+        let order = [
+            "first paragraph",
+            "Confirm: https://testproduct.com/confirm",
+            "second paragraph",
+            "Widget",
+            "Order: 42",
+            "last paragraph",
+        ];
+        assert!(order
+            .windows(2)
+            .all(|pair| position(&text, pair[0]) < position(&text, pair[1])));
+
+        // the fixed sections replace their blocks in place
+        let email = EmailBuilder::new()
+            .intro("old intro")
+            .action(Action::new("Old", "https://testproduct.com/old"))
+            .outro("old outro")
+            .set_intros(vec!["new intro"])
+            .set_actions(vec![Action::new("New", "https://testproduct.com/new")])
+            .set_outros(vec!["new outro", "another outro"])
+            .build();
+        let html = mailgen.render_html(&email)?;
+        assert!(
+            !html.contains("old intro")
+                && !html.contains("testproduct.com/old")
+                && !html.contains("old outro")
+        );
+        let order = [
+            ">new intro</p>",
+            "testproduct.com/new",
+            ">new outro</p>",
+            ">another outro</p>",
+        ];
+        assert!(order
+            .windows(2)
+            .all(|pair| position(&html, pair[0]) < position(&html, pair[1])));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_verification_code() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+        use crate::VerificationCode;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .intro("Your login code is:")
+            .code(VerificationCode::new("482913").expiry("This code expires in 10 minutes."))
+            .outro("If you didn't request it, ignore this email.")
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("class=\"body-code_value\" dir=\"ltr\""));
+        assert!(html.contains("letter-spacing: 8px;"));
+        assert!(html.contains("user-select: all;\">482913</p>"));
+        assert!(html.contains(">This code expires in 10 minutes.</p>"));
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("\n\n482913\n\nThis code expires in 10 minutes.\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_callouts() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+        use crate::Callout;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .callout(Callout::danger("Please update your card.").title("Your payment failed"))
+            .callout(Callout::info("This link expires in 24h."))
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains("class=\"body-callout_cell body-callout_danger\""));
+        assert!(html.contains("border-color: #DC4D2F;"));
+        assert!(html.contains("color: #DC4D2F;\">Your payment failed</p>"));
+        assert!(html.contains(">Please update your card.</p>"));
+        assert!(html.contains("class=\"body-callout_cell body-callout_info\""));
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("DANGER: Your payment failed\nPlease update your card.\n"));
+        assert!(text.contains("INFO: This link expires in 24h.\n"));
+
+        // severity defaults to info when deserialized
+        let email: crate::Email<'static> =
+            serde_json::from_str(r#"{"blocks": [{"type": "callout", "text": "Heads up"}]}"#)?;
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("INFO: Heads up"));
+
+        // labels are localized
+        let email = EmailBuilder::new()
+            .callout(Callout::warning("Dein Passwort läuft bald ab."))
+            .locale("de")
+            .build();
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("WARNUNG: Dein Passwort läuft bald ab.\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_lists() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+        use crate::List;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .intro("Next steps:")
+            .list(
+                List::ordered()
+                    .item("Verify your email")
+                    .item_with_children(
+                        "Set up your profile",
+                        vec!["Add a photo", "<b>Write a bio</b>"],
+                    )
+                    .item("Invite your team"),
+            )
+            .list(
+                List::unordered()
+                    .item("Unlimited projects")
+                    .item("Priority support")
+                    .item("Single sign-on for your whole organization with automatic provisioning of new accounts"),
+            )
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        assert!(html.contains(">2.</td>"));
+        assert!(html.contains(">Set up your profile\n"));
+        assert!(html.contains(">&lt;b&gt;Write a bio&lt;/b&gt;</td>"));
+        assert!(html.contains(">•</td>"));
+        assert_eq!(html.matches("class=\"body-list\"").count(), 3);
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains(
+            "1. Verify your email\n2. Set up your profile\n   1. Add a photo\n   2. <b>Write a bio</b>\n3. Invite your team\n"
+        ));
+        assert!(text.contains("- Unlimited projects\n- Priority support\n"));
+        // long items wrap with continuation lines indented under the marker
+        assert!(text.contains(
+            "- Single sign-on for your whole organization with automatic provisioning of new\n  accounts\n"
+        ));
+
+        // items can be texts or objects with nested items
+        let email: crate::Email<'static> = serde_json::from_str(
+            r#"{"blocks": [{"type": "list", "items": ["One", {"text": "Two", "children": ["Three"]}]}]}"#,
+        )?;
+        let text = mailgen.render_text(&email)?;
+        assert!(text.contains("- One\n- Two\n   - Three\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "default-theme")]
+    fn test_images() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::themes::DefaultTheme;
+        use crate::Image;
+
+        let theme = DefaultTheme::new()?;
+        let branding = Branding::new("test product", "https://testproduct.com");
+        let mailgen = Mailgen::new(theme, branding);
+
+        let email = EmailBuilder::new()
+            .hero(Image::new(
+                "https://testproduct.com/hero.png",
+                "Summer sale",
+            ))
+            .intro("Check out our new product:")
+            .image(
+                Image::new("https://testproduct.com/product.png", "New product")
+                    .width(200)
+                    .link("https://testproduct.com/product"),
+            )
+            .image(Image::new("https://testproduct.com/divider.png", ""))
+            .image(
+                Image::new("https://testproduct.com/banner.png", "")
+                    .link("https://testproduct.com/sale"),
+            )
+            .image(Image::new("javascript:alert(1)", "<script>"))
+            .build();
+
+        let html = mailgen.render_html(&email)?;
+        // the hero spans the email body, images in the content are limited to the content width
+        assert!(html.contains(
+            "src=\"https://testproduct.com/hero.png\" alt=\"Summer sale\" width=\"570\""
+        ));
+        assert!(html.contains("width: 100%;max-width: 570px\""));
+        assert!(html.contains("<a href=\"https://testproduct.com/product\""));
+        assert!(html.contains("alt=\"New product\" width=\"200\""));
+        assert!(html.contains("alt=\"\" width=\"500\""));
+        assert!(!html.contains("javascript:"));
+        assert!(html.find("hero.png").unwrap() < html.find("Check out our new product").unwrap());
+
+        let text = mailgen.render_text(&email)?;
+        assert!(text.starts_with("[Summer sale]\n"));
+        assert!(text.contains("[New product]: https://testproduct.com/product\n"));
+        // linked images without alternative text keep their link
+        assert!(text.contains("\nhttps://testproduct.com/sale\n"));
+        assert!(!text.contains("[]"));
+        assert!(!text.contains("divider.png"));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,188 @@
+//! Rendering of markdown texts to sanitized inline HTML
+
+use pulldown_cmark::{html, CowStr, Event, Parser, Tag, TagEnd};
+
+use crate::Text;
+
+/// Renders markdown texts to HTML, other texts are returned as is
+pub(crate) fn render(text: Text) -> Text {
+    match text {
+        Text::Markdown(markdown) => Text::Html(to_html(&markdown).into()),
+        text => text,
+    }
+}
+
+/// Block constructs are flattened to their inline content, as the texts are placed in paragraphs
+/// of the template: paragraphs, headings and code blocks are separated by line breaks, list items
+/// are prefixed with their marker and headings are emphasized. Raw HTML is escaped and URLs are sanitized.
+fn to_html(markdown: &str) -> String {
+    let mut events = Vec::new();
+    let mut flattener = Flattener::default();
+    for event in Parser::new(markdown) {
+        flattener.push(event, &mut events);
+    }
+
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+    output.trim_end().to_string()
+}
+
+/// State of the flattening of block constructs
+#[derive(Default)]
+struct Flattener {
+    /// Whether a block has been emitted, the next one is separated from it
+    started: bool,
+    /// Whether the last block is a list item, consecutive items are separated by single line breaks
+    item: bool,
+    /// Whether the last event was the marker of an item, its first paragraph follows the marker directly
+    marker: bool,
+    /// Next number of the open ordered lists, `None` for bulleted lists
+    lists: Vec<Option<u64>>,
+    code_block: bool,
+}
+
+impl Flattener {
+    fn push<'a>(&mut self, event: Event<'a>, events: &mut Vec<Event<'a>>) {
+        let after_marker = std::mem::take(&mut self.marker);
+
+        match event {
+            Event::Start(Tag::Paragraph) if after_marker => {}
+            Event::Start(Tag::Paragraph) => self.separate(events),
+            Event::Start(Tag::Heading { .. }) => {
+                self.separate(events);
+                events.push(Event::Start(Tag::Strong));
+            }
+            Event::End(TagEnd::Heading(_)) => events.push(Event::End(TagEnd::Strong)),
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.separate(events);
+                self.code_block = true;
+            }
+            Event::End(TagEnd::CodeBlock) => self.code_block = false,
+            Event::Text(text) if self.code_block => {
+                for (index, line) in text.lines().enumerate() {
+                    if index > 0 {
+                        events.push(Event::InlineHtml("<br />".into()));
+                    }
+                    events.push(Event::Code(line.to_string().into()));
+                }
+            }
+            Event::Start(Tag::List(start)) => self.lists.push(start),
+            Event::End(TagEnd::List(_)) => {
+                self.lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                if self.started {
+                    let separator = if self.item { "<br />" } else { "<br /><br />" };
+                    events.push(Event::InlineHtml(separator.into()));
+                }
+                self.started = true;
+                self.item = true;
+                self.marker = true;
+
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                if depth > 0 {
+                    events.push(Event::InlineHtml("&nbsp;&nbsp;&nbsp;".repeat(depth).into()));
+                }
+                events.push(Event::Text(marker.into()));
+            }
+            // the following block is separated anyway
+            Event::Rule
+            | Event::Start(Tag::BlockQuote(_) | Tag::HtmlBlock)
+            | Event::End(
+                TagEnd::Paragraph | TagEnd::BlockQuote(_) | TagEnd::HtmlBlock | TagEnd::Item,
+            ) => {}
+            Event::Html(html) | Event::InlineHtml(html) => events.push(Event::Text(html)),
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => events.push(Event::Start(Tag::Link {
+                link_type,
+                dest_url: sanitize(dest_url),
+                title,
+                id,
+            })),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => events.push(Event::Start(Tag::Image {
+                link_type,
+                dest_url: sanitize(dest_url),
+                title,
+                id,
+            })),
+            event => events.push(event),
+        }
+    }
+
+    /// Separates the next block from the previous one
+    fn separate(&mut self, events: &mut Vec<Event>) {
+        if self.started {
+            events.push(Event::InlineHtml("<br /><br />".into()));
+        }
+        self.started = true;
+        self.item = false;
+    }
+}
+
+fn sanitize(url: CowStr) -> CowStr {
+    crate::url::sanitize(&url).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_html;
+
+    #[test]
+    fn inline() {
+        assert_eq!(
+            to_html("Some **bold** [link](javascript:alert(1))\n\n<b>raw</b>"),
+            "Some <strong>bold</strong> <a href=\"#\">link</a><br /><br />&lt;b&gt;raw&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        assert_eq!(
+            to_html("# Title\n\nText"),
+            "<strong>Title</strong><br /><br />Text"
+        );
+        assert_eq!(
+            to_html("Steps:\n\n1. One\n2. Two\n   - Nested\n\nDone"),
+            "Steps:<br /><br />1. One<br />2. Two<br />&nbsp;&nbsp;&nbsp;- Nested<br /><br />Done"
+        );
+        assert_eq!(to_html("- a\n\n- b"), "- a<br />- b");
+        assert_eq!(
+            to_html("```\nlet a = 1;\nlet b = <b>;\n```"),
+            "<code>let a = 1;</code><br /><code>let b = &lt;b&gt;;</code>"
+        );
+        assert_eq!(to_html("> Quoted\n>\n> Twice"), "Quoted<br /><br />Twice");
+        assert_eq!(to_html("Above\n\n---\n\nBelow"), "Above<br /><br />Below");
+
+        for markdown in ["# a", "- a", "1. a", "```\na\n```", "> a", "---", "    a"] {
+            let html = to_html(markdown);
+            for tag in [
+                "<h1",
+                "<ul",
+                "<ol",
+                "<li",
+                "<pre",
+                "<blockquote",
+                "<hr",
+                "<p>",
+            ] {
+                assert!(!html.contains(tag), "{markdown:?} rendered to {html:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,594 @@
+//! schema.org markup, embedded as JSON-LD so email clients like Gmail and Outlook can show
+//! inbox actions and rich cards for orders, deliveries, reservations and invoices.
+//!
+//! ```
+//! use mailgen::markup::{Offer, Order, OrderStatus, Organization, Product};
+//! use mailgen::EmailBuilder;
+//!
+//! let email = EmailBuilder::new()
+//!     .order(Order {
+//!         order_status: Some(OrderStatus::Processing),
+//!         price: Some("29.99".into()),
+//!         price_currency: Some("USD".into()),
+//!         accepted_offer: vec![Offer {
+//!             item_offered: Product::new("Chromecast"),
+//!             price: "29.99".into(),
+//!             price_currency: "USD".into(),
+//!         }],
+//!         ..Order::new(Organization::new("Test Shop"), "123-456")
+//!     })
+//!     .confirm_action("Approve", "https://test.com/approve", "Approve the expense")
+//!     .build();
+//!
+//! assert!(email.json_ld()?.unwrap().contains("\"@type\":\"Order\""));
+//! # Ok::<(), serde_json::Error>(())
+//! ```
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::email::Email;
+use crate::url;
+
+const SCHEMA_CONTEXT: &str = "http://schema.org";
+
+/// A single piece of schema.org markup
+///
+/// Deserializes from the schema.org representation, the variant is selected by its `@type`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged, from = "MarkupRepr<'a>")]
+pub enum Markup<'a> {
+    /// One-click action, e.g. approving an expense
+    ConfirmAction(ConfirmAction<'a>),
+    /// One-click action that saves something, e.g. a coupon
+    SaveAction(SaveAction<'a>),
+    /// Link to a page where the user can take action (Gmail Go-To Action)
+    ViewAction(ViewAction<'a>),
+    /// Order confirmation
+    Order(Order<'a>),
+    /// Shipping notification
+    ParcelDelivery(ParcelDelivery<'a>),
+    /// Reservation for an event
+    EventReservation(EventReservation<'a>),
+    /// Bill or invoice
+    Invoice(Invoice<'a>),
+}
+
+/// Handler that gets called via HTTP when a one-click action is executed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type")]
+pub struct HttpActionHandler<'a> {
+    /// Url called by the email client
+    pub url: Cow<'a, str>,
+}
+
+/// One-click action, e.g. approving an expense
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type")]
+pub struct ConfirmAction<'a> {
+    /// Button text
+    pub name: Cow<'a, str>,
+    /// Handler called when the action is executed
+    pub handler: HttpActionHandler<'a>,
+    /// Description of the action, part of the message the action belongs to in JSON-LD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+}
+
+/// One-click action that saves something, e.g. a coupon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type")]
+pub struct SaveAction<'a> {
+    /// Button text
+    pub name: Cow<'a, str>,
+    /// Handler called when the action is executed
+    pub handler: HttpActionHandler<'a>,
+    /// Description of the action, part of the message the action belongs to in JSON-LD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+}
+
+/// Link to a page where the user can take action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type")]
+pub struct ViewAction<'a> {
+    /// Button text
+    pub name: Cow<'a, str>,
+    /// Button link
+    pub url: Cow<'a, str>,
+    /// Description of the action, part of the message the action belongs to in JSON-LD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+}
+
+/// A company, e.g. the merchant of an order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct Organization<'a> {
+    pub name: Cow<'a, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Cow<'a, str>>,
+}
+
+impl<'a> Organization<'a> {
+    pub fn new<S: Into<Cow<'a, str>>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            url: None,
+        }
+    }
+}
+
+/// A person, e.g. the guest of a reservation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct Person<'a> {
+    pub name: Cow<'a, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<Cow<'a, str>>,
+}
+
+/// Physical address
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct PostalAddress<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub street_address: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_locality: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_region: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_country: Option<Cow<'a, str>>,
+}
+
+/// A product, e.g. an ordered or shipped item
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct Product<'a> {
+    pub name: Cow<'a, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sku: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<Cow<'a, str>>,
+}
+
+impl<'a> Product<'a> {
+    pub fn new<S: Into<Cow<'a, str>>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// An ordered product with its price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct Offer<'a> {
+    pub item_offered: Product<'a>,
+    pub price: Cow<'a, str>,
+    pub price_currency: Cow<'a, str>,
+}
+
+/// A price, optionally with its currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct PriceSpecification<'a> {
+    pub price: Cow<'a, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_currency: Option<Cow<'a, str>>,
+}
+
+/// Status of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderStatus {
+    #[serde(rename = "http://schema.org/OrderProcessing")]
+    Processing,
+    #[serde(rename = "http://schema.org/OrderInTransit")]
+    InTransit,
+    #[serde(rename = "http://schema.org/OrderDelivered")]
+    Delivered,
+    #[serde(rename = "http://schema.org/OrderPickupAvailable")]
+    PickupAvailable,
+    #[serde(rename = "http://schema.org/OrderPaymentDue")]
+    PaymentDue,
+    #[serde(rename = "http://schema.org/OrderProblem")]
+    Problem,
+    #[serde(rename = "http://schema.org/OrderCancelled")]
+    Cancelled,
+    #[serde(rename = "http://schema.org/OrderReturned")]
+    Returned,
+}
+
+/// Order confirmation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct Order<'a> {
+    pub merchant: Organization<'a>,
+    pub order_number: Cow<'a, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_status: Option<OrderStatus>,
+    /// Date of the order in ISO 8601 format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_date: Option<Cow<'a, str>>,
+    /// Total price of the order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_currency: Option<Cow<'a, str>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accepted_offer: Vec<Offer<'a>>,
+    /// Link to the order details
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub potential_action: Option<ViewAction<'a>>,
+}
+
+impl<'a> Order<'a> {
+    pub fn new<S: Into<Cow<'a, str>>>(merchant: Organization<'a>, order_number: S) -> Self {
+        Self {
+            merchant,
+            order_number: order_number.into(),
+            order_status: None,
+            order_date: None,
+            price: None,
+            price_currency: None,
+            accepted_offer: Vec::new(),
+            url: None,
+            potential_action: None,
+        }
+    }
+}
+
+/// Shipping notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct ParcelDelivery<'a> {
+    /// Order the delivery belongs to
+    pub part_of_order: Box<Order<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carrier: Option<Organization<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery_address: Option<PostalAddress<'a>>,
+    /// Earliest expected arrival in ISO 8601 format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_arrival_from: Option<Cow<'a, str>>,
+    /// Latest expected arrival in ISO 8601 format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_arrival_until: Option<Cow<'a, str>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub item_shipped: Vec<Product<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_number: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_url: Option<Cow<'a, str>>,
+}
+
+impl<'a> ParcelDelivery<'a> {
+    pub fn new(part_of_order: Order<'a>) -> Self {
+        Self {
+            part_of_order: Box::new(part_of_order),
+            carrier: None,
+            delivery_address: None,
+            expected_arrival_from: None,
+            expected_arrival_until: None,
+            item_shipped: Vec::new(),
+            tracking_number: None,
+            tracking_url: None,
+        }
+    }
+}
+
+/// Status of a reservation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReservationStatus {
+    #[serde(rename = "http://schema.org/ReservationConfirmed")]
+    Confirmed,
+    #[serde(rename = "http://schema.org/ReservationPending")]
+    Pending,
+    #[serde(rename = "http://schema.org/ReservationHold")]
+    Hold,
+    #[serde(rename = "http://schema.org/ReservationCancelled")]
+    Cancelled,
+}
+
+/// Location of an event
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct Place<'a> {
+    pub name: Cow<'a, str>,
+    pub address: PostalAddress<'a>,
+}
+
+/// An event, e.g. a concert or a conference
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct Event<'a> {
+    pub name: Cow<'a, str>,
+    /// Start of the event in ISO 8601 format
+    pub start_date: Cow<'a, str>,
+    /// End of the event in ISO 8601 format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<Cow<'a, str>>,
+    pub location: Place<'a>,
+}
+
+/// Reservation for an event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct EventReservation<'a> {
+    pub reservation_number: Cow<'a, str>,
+    pub reservation_status: ReservationStatus,
+    pub under_name: Person<'a>,
+    pub reservation_for: Event<'a>,
+    /// Link to manage the reservation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Cow<'a, str>>,
+}
+
+/// Payment status of an invoice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentStatus {
+    #[serde(rename = "http://schema.org/PaymentDue")]
+    Due,
+    #[serde(rename = "http://schema.org/PaymentPastDue")]
+    PastDue,
+    #[serde(rename = "http://schema.org/PaymentComplete")]
+    Complete,
+    #[serde(rename = "http://schema.org/PaymentDeclined")]
+    Declined,
+    #[serde(rename = "http://schema.org/PaymentAutomaticallyApplied")]
+    AutomaticallyApplied,
+}
+
+/// Bill or invoice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "@type", rename_all = "camelCase")]
+pub struct Invoice<'a> {
+    pub provider: Organization<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_status: Option<PaymentStatus>,
+    /// Due date in ISO 8601 format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_due_date: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_payment_due: Option<PriceSpecification<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum_payment_due: Option<PriceSpecification<'a>>,
+    /// Link to the invoice
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub potential_action: Option<ViewAction<'a>>,
+}
+
+impl<'a> Invoice<'a> {
+    pub fn new(provider: Organization<'a>) -> Self {
+        Self {
+            provider,
+            account_id: None,
+            payment_status: None,
+            payment_due_date: None,
+            total_payment_due: None,
+            minimum_payment_due: None,
+            url: None,
+            potential_action: None,
+        }
+    }
+}
+
+macro_rules! impl_from_markup {
+    ($($variant:ident),*) => {
+        $(
+            impl<'a> From<$variant<'a>> for Markup<'a> {
+                fn from(value: $variant<'a>) -> Self {
+                    Markup::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "@type")]
+enum MarkupRepr<'a> {
+    ConfirmAction(ConfirmAction<'a>),
+    SaveAction(SaveAction<'a>),
+    ViewAction(ViewAction<'a>),
+    Order(Order<'a>),
+    ParcelDelivery(ParcelDelivery<'a>),
+    EventReservation(EventReservation<'a>),
+    Invoice(Invoice<'a>),
+}
+
+impl<'a> From<MarkupRepr<'a>> for Markup<'a> {
+    fn from(value: MarkupRepr<'a>) -> Self {
+        match value {
+            MarkupRepr::ConfirmAction(v) => Markup::ConfirmAction(v),
+            MarkupRepr::SaveAction(v) => Markup::SaveAction(v),
+            MarkupRepr::ViewAction(v) => Markup::ViewAction(v),
+            MarkupRepr::Order(v) => Markup::Order(v),
+            MarkupRepr::ParcelDelivery(v) => Markup::ParcelDelivery(v),
+            MarkupRepr::EventReservation(v) => Markup::EventReservation(v),
+            MarkupRepr::Invoice(v) => Markup::Invoice(v),
+        }
+    }
+}
+
+impl_from_markup!(
+    ConfirmAction,
+    SaveAction,
+    ViewAction,
+    Order,
+    ParcelDelivery,
+    EventReservation,
+    Invoice
+);
+
+/// Top level JSON-LD item
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Item<'a> {
+    /// Actions are wrapped in the message they belong to
+    EmailMessage {
+        #[serde(rename = "@context")]
+        context: &'static str,
+        #[serde(rename = "@type")]
+        kind: &'static str,
+        #[serde(rename = "potentialAction")]
+        potential_action: Box<Markup<'a>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<Cow<'a, str>>,
+    },
+    Entity {
+        #[serde(rename = "@context")]
+        context: &'static str,
+        #[serde(flatten)]
+        markup: Box<Markup<'a>>,
+    },
+}
+
+impl<'a> From<&Markup<'a>> for Item<'a> {
+    fn from(markup: &Markup<'a>) -> Self {
+        let mut markup = markup.clone();
+        markup.sanitize_urls();
+
+        // the description belongs to the message, not the action
+        let description = match &mut markup {
+            Markup::ConfirmAction(action) => action.description.take(),
+            Markup::SaveAction(action) => action.description.take(),
+            Markup::ViewAction(action) => action.description.take(),
+            _ => {
+                return Item::Entity {
+                    context: SCHEMA_CONTEXT,
+                    markup: Box::new(markup),
+                }
+            }
+        };
+
+        Item::EmailMessage {
+            context: SCHEMA_CONTEXT,
+            kind: "EmailMessage",
+            potential_action: Box::new(markup),
+            description,
+        }
+    }
+}
+
+fn sanitize(link: &mut Cow<'_, str>) {
+    *link = Cow::Owned(url::sanitize(link));
+}
+
+impl Markup<'_> {
+    /// Replaces links with schemes that aren't allowed, see [`url::sanitize`]
+    fn sanitize_urls(&mut self) {
+        match self {
+            Markup::ConfirmAction(action) => sanitize(&mut action.handler.url),
+            Markup::SaveAction(action) => sanitize(&mut action.handler.url),
+            Markup::ViewAction(action) => sanitize(&mut action.url),
+            Markup::Order(order) => order.sanitize_urls(),
+            Markup::ParcelDelivery(delivery) => {
+                delivery.part_of_order.sanitize_urls();
+                delivery
+                    .carrier
+                    .iter_mut()
+                    .for_each(Organization::sanitize_urls);
+                delivery
+                    .item_shipped
+                    .iter_mut()
+                    .for_each(Product::sanitize_urls);
+                delivery.tracking_url.iter_mut().for_each(sanitize);
+            }
+            Markup::EventReservation(reservation) => {
+                reservation.url.iter_mut().for_each(sanitize);
+            }
+            Markup::Invoice(invoice) => {
+                invoice.provider.sanitize_urls();
+                invoice.url.iter_mut().for_each(sanitize);
+                if let Some(action) = &mut invoice.potential_action {
+                    sanitize(&mut action.url);
+                }
+            }
+        }
+    }
+}
+
+impl Organization<'_> {
+    fn sanitize_urls(&mut self) {
+        self.url.iter_mut().for_each(sanitize);
+    }
+}
+
+impl Product<'_> {
+    fn sanitize_urls(&mut self) {
+        self.url.iter_mut().for_each(sanitize);
+        self.image.iter_mut().for_each(sanitize);
+    }
+}
+
+impl Order<'_> {
+    fn sanitize_urls(&mut self) {
+        self.merchant.sanitize_urls();
+        for offer in &mut self.accepted_offer {
+            offer.item_offered.sanitize_urls();
+        }
+        self.url.iter_mut().for_each(sanitize);
+        if let Some(action) = &mut self.potential_action {
+            sanitize(&mut action.url);
+        }
+    }
+}
+
+/// Serializes the markup of the email, `None` if the email has no markup
+pub(crate) fn json_ld(email: &Email) -> Result<Option<String>, serde_json::Error> {
+    let go_to_action = email.go_to_action.as_ref().map(|action| {
+        Markup::ViewAction(ViewAction {
+            name: action.text.to_string().into(),
+            url: action.link.clone(),
+            description: Some(action.description.to_string().into()),
+        })
+    });
+
+    let markup: Vec<&Markup> = go_to_action
+        .iter()
+        .chain(email.markup.iter().flatten())
+        .collect();
+
+    let json = match markup.as_slice() {
+        [] => return Ok(None),
+        [markup] => serde_json::to_string(&Item::from(*markup))?,
+        markup => {
+            serde_json::to_string(&markup.iter().map(|m| Item::from(*m)).collect::<Vec<_>>())?
+        }
+    };
+
+    Ok(Some(escape_script(&json)))
+}
+
+/// Escapes characters in serialized JSON that could close or confuse the surrounding `<script>` tag.
+/// The escapes are valid JSON string escapes, so the data itself is unchanged.
+fn escape_script(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        match c {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,654 @@
+//! Generation of complete MIME messages (RFC 5322 / RFC 2045) containing the rendered
+//! text and HTML versions of an email as `multipart/alternative`.
+//!
+//! Images can be embedded as `multipart/related` parts (see [`InlineImage`]), so they show up
+//! in clients that block remote images.
+
+use std::fmt::{Display, Write};
+use std::hash::{BuildHasher, RandomState};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+/// Maximum length of a header line (without CRLF) as recommended by RFC 5322
+const LINE_LENGTH: usize = 78;
+/// Maximum length of a line (without CRLF) allowed by RFC 5322
+const MAX_LINE_LENGTH: usize = 998;
+
+/// E-Mail address with an optional display name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mailbox {
+    pub name: Option<String>,
+    pub address: String,
+}
+
+impl Mailbox {
+    pub fn new<S: Into<String>>(address: S) -> Self {
+        Self {
+            name: None,
+            address: address.into(),
+        }
+    }
+
+    pub fn with_name<S: Into<String>>(name: S, address: S) -> Self {
+        Self {
+            name: Some(name.into()),
+            address: address.into(),
+        }
+    }
+
+    /// Domain part of the address
+    fn domain(&self) -> &str {
+        self.address
+            .rsplit_once('@')
+            .map_or("localhost", |(_, domain)| domain)
+    }
+}
+
+impl Display for Mailbox {
+    /// Formats the mailbox for a header, encoding the display name if necessary
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let address = sanitize(&self.address);
+        let address: String = address
+            .chars()
+            .filter(|c| !matches!(c, '<' | '>'))
+            .collect();
+
+        match self.name.as_deref().map(sanitize) {
+            Some(name) if !name.is_empty() => {
+                if name.is_ascii() {
+                    let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+                    write!(f, "\"{name}\" <{address}>")
+                } else {
+                    write!(f, "{} <{address}>", encode_words(&name).join(" "))
+                }
+            }
+            _ => write!(f, "<{address}>"),
+        }
+    }
+}
+
+/// Image embedded into the message and referenced from the HTML via a `cid:` url
+///
+/// Every `src="..."` attribute in the rendered HTML matching [`InlineImage::src`] (e.g. the logo
+/// of the branding) is rewritten to point to the embedded image. Images that aren't referenced
+/// are not attached.
+///
+/// ```
+/// use mailgen::message::InlineImage;
+///
+/// let logo = InlineImage::new("logo.png", "image/png", vec![0x89, 0x50, 0x4e, 0x47]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineImage {
+    /// Reference of the image in the email, e.g. `Branding::logo`
+    pub src: String,
+    /// MIME type, e.g. `image/png`
+    pub content_type: String,
+    /// Image data
+    pub data: Vec<u8>,
+    /// File name suggested to the email client
+    pub filename: Option<String>,
+}
+
+impl InlineImage {
+    pub fn new<S: Into<String>>(src: S, content_type: S, data: Vec<u8>) -> Self {
+        Self {
+            src: src.into(),
+            content_type: content_type.into(),
+            data,
+            filename: None,
+        }
+    }
+
+    /// Reads the image from a file, the content type is guessed from the file extension
+    pub fn from_file<S: Into<String>, P: AsRef<Path>>(src: S, path: P) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let content_type = match extension.as_deref() {
+            Some("png") => "image/png",
+            Some("jpg" | "jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            Some("svg") => "image/svg+xml",
+            _ => "application/octet-stream",
+        };
+
+        Ok(Self {
+            src: src.into(),
+            content_type: content_type.to_string(),
+            data: std::fs::read(path)?,
+            filename: path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(str::to_string),
+        })
+    }
+
+    /// Set the file name suggested to the email client
+    #[must_use]
+    pub fn filename<S: Into<String>>(mut self, filename: S) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+}
+
+/// Content transfer encoding of the text and HTML parts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransferEncoding {
+    /// Readable for mostly ASCII content, the default
+    #[default]
+    QuotedPrintable,
+    /// Compact for mostly non-ASCII content
+    Base64,
+}
+
+impl TransferEncoding {
+    fn name(self) -> &'static str {
+        match self {
+            TransferEncoding::QuotedPrintable => "quoted-printable",
+            TransferEncoding::Base64 => "base64",
+        }
+    }
+
+    fn encode(self, data: &[u8]) -> String {
+        match self {
+            TransferEncoding::QuotedPrintable => quoted_printable::encode_to_str(data),
+            TransferEncoding::Base64 => base64_lines(data),
+        }
+    }
+}
+
+/// Headers and options of a message generated by [`Mailgen::render_message`](crate::Mailgen::render_message)
+///
+/// ```
+/// use mailgen::message::{Mailbox, MessageHeaders};
+///
+/// let headers = MessageHeaders::new(
+///     Mailbox::with_name("Test Product", "no-reply@testproduct.com"),
+///     Mailbox::new("user@example.com"),
+/// )
+/// .subject("Welcome!");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageHeaders {
+    pub from: Mailbox,
+    pub to: Vec<Mailbox>,
+    pub cc: Vec<Mailbox>,
+    pub reply_to: Option<Mailbox>,
+    /// Subject line, defaults to the subject (or summary) of the email
+    pub subject: Option<String>,
+    /// Encoding of the text and HTML parts
+    pub transfer_encoding: TransferEncoding,
+    /// Images embedded into this message, in addition to the ones of [`Mailgen`](crate::Mailgen)
+    pub inline_images: Vec<InlineImage>,
+}
+
+impl MessageHeaders {
+    pub fn new(from: Mailbox, to: Mailbox) -> Self {
+        Self {
+            from,
+            to: vec![to],
+            cc: Vec::new(),
+            reply_to: None,
+            subject: None,
+            transfer_encoding: TransferEncoding::default(),
+            inline_images: Vec::new(),
+        }
+    }
+
+    /// Add a recipient
+    #[must_use]
+    pub fn to(mut self, to: Mailbox) -> Self {
+        self.to.push(to);
+        self
+    }
+
+    /// Add a carbon copy recipient
+    #[must_use]
+    pub fn cc(mut self, cc: Mailbox) -> Self {
+        self.cc.push(cc);
+        self
+    }
+
+    /// Set the address replies are sent to
+    #[must_use]
+    pub fn reply_to(mut self, reply_to: Mailbox) -> Self {
+        self.reply_to = Some(reply_to);
+        self
+    }
+
+    /// Set the subject line
+    #[must_use]
+    pub fn subject<S: Into<String>>(mut self, subject: S) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Set the encoding of the text and HTML parts
+    #[must_use]
+    pub fn transfer_encoding(mut self, transfer_encoding: TransferEncoding) -> Self {
+        self.transfer_encoding = transfer_encoding;
+        self
+    }
+
+    /// Embed an image into this message
+    #[must_use]
+    pub fn inline_image(mut self, image: InlineImage) -> Self {
+        self.inline_images.push(image);
+        self
+    }
+}
+
+/// MIME body part
+pub(crate) enum Part {
+    Single {
+        content_type: String,
+        encoding: TransferEncoding,
+        headers: Vec<(&'static str, String)>,
+        data: Vec<u8>,
+    },
+    Multipart {
+        /// Subtype including additional parameters, e.g. `related; type="text/html"`
+        subtype: &'static str,
+        parts: Vec<Part>,
+    },
+}
+
+impl Part {
+    pub(crate) fn text(subtype: &str, content: &str, encoding: TransferEncoding) -> Self {
+        Part::Single {
+            content_type: format!("text/{subtype}; charset=utf-8"),
+            encoding,
+            headers: Vec::new(),
+            data: normalize_line_endings(content).into_bytes(),
+        }
+    }
+
+    /// HTML part with the referenced inline images as `multipart/related`
+    pub(crate) fn html<'a, I>(html: &str, images: I, headers: &MessageHeaders) -> Self
+    where
+        I: IntoIterator<Item = &'a InlineImage>,
+    {
+        let mut html = html.to_string();
+        let mut parts = Vec::new();
+
+        for (content_id, image) in embed(&mut html, images, headers.from.domain()) {
+            let mut part_headers = vec![("Content-ID", format!("<{content_id}>"))];
+            let disposition = match image.filename.as_deref().map(sanitize) {
+                Some(filename) => format!("inline; filename=\"{}\"", filename.replace('"', "")),
+                None => "inline".to_string(),
+            };
+            part_headers.push(("Content-Disposition", disposition));
+
+            parts.push(Part::Single {
+                content_type: sanitize(&image.content_type),
+                encoding: TransferEncoding::Base64,
+                headers: part_headers,
+                data: image.data.clone(),
+            });
+        }
+
+        let html = Part::text("html", &html, headers.transfer_encoding);
+        if parts.is_empty() {
+            return html;
+        }
+
+        parts.insert(0, html);
+        Part::Multipart {
+            subtype: "related; type=\"text/html\"",
+            parts,
+        }
+    }
+
+    /// Writes the headers and the body of the part
+    fn write(&self, out: &mut String) {
+        match self {
+            Part::Single {
+                content_type,
+                encoding,
+                headers,
+                data,
+            } => {
+                write_header(out, "Content-Type", content_type);
+                write_header(out, "Content-Transfer-Encoding", encoding.name());
+                for (name, value) in headers {
+                    write_header(out, name, value);
+                }
+                out.push_str("\r\n");
+                out.push_str(&encoding.encode(data));
+                if !out.ends_with("\r\n") {
+                    out.push_str("\r\n");
+                }
+            }
+            Part::Multipart { subtype, parts } => {
+                let boundary = format!("mailgen-{}", random_hex());
+                write_header(
+                    out,
+                    "Content-Type",
+                    &format!("multipart/{subtype}; boundary=\"{boundary}\""),
+                );
+                out.push_str("\r\n");
+                for part in parts {
+                    let _ = write!(out, "--{boundary}\r\n");
+                    part.write(out);
+                }
+                let _ = write!(out, "--{boundary}--\r\n");
+            }
+        }
+    }
+}
+
+/// Rewrites the references of the images in the HTML to `cid:` urls with content ids on the domain.
+/// Returns the referenced images with their content ids, images that aren't referenced are skipped
+pub(crate) fn embed<'a, I>(
+    html: &mut String,
+    images: I,
+    domain: &str,
+) -> Vec<(String, &'a InlineImage)>
+where
+    I: IntoIterator<Item = &'a InlineImage>,
+{
+    let mut embedded = Vec::new();
+    for image in images {
+        let content_id = format!("{}@{}", random_hex(), sanitize(domain));
+        let cid = format!("src=\"cid:{content_id}\"");
+
+        let mut referenced = false;
+        for src in attribute_values(&image.src) {
+            let attribute = format!("src=\"{src}\"");
+            if html.contains(&attribute) {
+                *html = html.replace(&attribute, &cid);
+                referenced = true;
+            }
+        }
+        if referenced {
+            embedded.push((content_id, image));
+        }
+    }
+    embedded
+}
+
+/// Host of a link (e.g. the link of the branding), `localhost` if it has none
+#[cfg(feature = "lettre")]
+pub(crate) fn host(link: &str) -> &str {
+    let rest = link.split_once("://").map_or(link, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    match host.split(':').next() {
+        Some(host) if !host.is_empty() => host,
+        _ => "localhost",
+    }
+}
+
+/// Builds the complete message from the headers and the body
+pub(crate) fn build(headers: &MessageHeaders, subject: Option<&str>, body: Part) -> String {
+    let mut out = String::new();
+
+    write_header(&mut out, "From", &headers.from.to_string());
+    write_mailboxes(&mut out, "To", &headers.to);
+    write_mailboxes(&mut out, "Cc", &headers.cc);
+    if let Some(reply_to) = &headers.reply_to {
+        write_header(&mut out, "Reply-To", &reply_to.to_string());
+    }
+    if let Some(subject) = headers.subject.as_deref().or(subject) {
+        write_header(&mut out, "Subject", &encode_header_text(subject));
+    }
+    write_header(&mut out, "Date", &format_date(SystemTime::now()));
+    write_header(
+        &mut out,
+        "Message-ID",
+        &format!("<{}@{}>", random_hex(), sanitize(headers.from.domain())),
+    );
+    write_header(&mut out, "MIME-Version", "1.0");
+
+    body.write(&mut out);
+    out
+}
+
+/// Forms an attribute value can take in the rendered HTML, depending on how the theme escaped it
+fn attribute_values(value: &str) -> Vec<String> {
+    let escaped = value.replace('&', "&amp;").replace('"', "&quot;");
+    let fully_escaped = escaped
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&#x27;")
+        .replace('/', "&#x2f;");
+
+    let mut values = vec![value.to_string(), escaped, fully_escaped];
+    values.dedup();
+    values
+}
+
+fn write_mailboxes(out: &mut String, name: &str, mailboxes: &[Mailbox]) {
+    if mailboxes.is_empty() {
+        return;
+    }
+
+    let value = mailboxes
+        .iter()
+        .map(Mailbox::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    write_header(out, name, &value);
+}
+
+/// Writes a header, folding it at whitespace so lines stay within the recommended length.
+/// Whitespace is kept as is, words that don't fit on a line of the maximum length are split
+/// into encoded words
+fn write_header(out: &mut String, name: &str, value: &str) {
+    out.push_str(name);
+    out.push(':');
+    let mut line_length = name.len() + 1;
+    if !value.is_empty() {
+        out.push(' ');
+        line_length += 1;
+    }
+    let mut written = false;
+
+    let mut rest = value;
+    while !rest.is_empty() {
+        let start = rest.find(|c| c != ' ' && c != '\t').unwrap_or(rest.len());
+        let end = rest[start..]
+            .find([' ', '\t'])
+            .map_or(rest.len(), |end| start + end);
+        let (space, word) = (&rest[..start], &rest[start..end]);
+        rest = &rest[end..];
+
+        let offset = if written { 0 } else { line_length };
+        let pieces = if offset + space.len() + word.len() > MAX_LINE_LENGTH {
+            // whitespace between adjacent encoded words is dropped when decoding
+            encode_words(word)
+        } else {
+            vec![word.to_string()]
+        };
+
+        for (i, piece) in pieces.iter().enumerate() {
+            let space = if i == 0 { space } else { " " };
+            // lines can only be folded in front of whitespace
+            if written && !space.is_empty() && line_length + space.len() + piece.len() > LINE_LENGTH
+            {
+                out.push_str("\r\n");
+                line_length = 0;
+            }
+            out.push_str(space);
+            out.push_str(piece);
+            line_length += space.len() + piece.len();
+            written = true;
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// Removes line breaks so values can't inject additional headers
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if matches!(c, '\r' | '\n') { ' ' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Encodes unstructured header text (e.g. the subject) as RFC 2047 encoded words if it isn't ASCII
+fn encode_header_text(value: &str) -> String {
+    let value = sanitize(value);
+    if value.is_ascii() {
+        value
+    } else {
+        encode_words(&value).join(" ")
+    }
+}
+
+/// Splits the value into base64 encoded words that stay within the 75 character limit of RFC 2047
+fn encode_words(value: &str) -> Vec<String> {
+    // 45 bytes encode to 60 base64 characters, plus 12 characters for `=?utf-8?b??=`
+    const CHUNK: usize = 45;
+
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in value.chars() {
+        if chunk.len() + c.len_utf8() > CHUNK {
+            words.push(format!("=?utf-8?b?{}?=", BASE64.encode(&chunk)));
+            chunk.clear();
+        }
+        chunk.push(c);
+    }
+    if !chunk.is_empty() {
+        words.push(format!("=?utf-8?b?{}?=", BASE64.encode(&chunk)));
+    }
+    words
+}
+
+/// Base64 with lines of 76 characters as required by RFC 2045
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = BASE64.encode(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / 76 * 2 + 2);
+    for line in encoded.as_bytes().chunks(76) {
+        // base64 output is ASCII
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn normalize_line_endings(content: &str) -> String {
+    content.replace("\r\n", "\n").replace('\n', "\r\n")
+}
+
+/// Random hex string for boundaries and message ids, unique but not cryptographically secure
+fn random_hex() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let state = RandomState::new();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let first = state.hash_one((nanos, COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let second = state.hash_one(first);
+    format!("{first:016x}{second:016x}")
+}
+
+/// Formats a timestamp as RFC 5322 date in UTC, e.g. `Sat, 17 Oct 2026 09:05:00 +0000`
+fn format_date(time: SystemTime) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let days = seconds / 86_400;
+    let (hour, minute, second) = (seconds % 86_400 / 3600, seconds % 3600 / 60, seconds % 60);
+
+    // civil date from days since epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{}, {day:02} {} {year} {hour:02}:{minute:02}:{second:02} +0000",
+        DAYS[(days % 7) as usize],
+        MONTHS[(month - 1) as usize],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{encode_header_text, format_date, write_header, Mailbox, BASE64};
+    use base64::Engine;
+
+    #[test]
+    fn date() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_792_227_900);
+        assert_eq!(format_date(time), "Sat, 17 Oct 2026 09:05:00 +0000");
+        assert_eq!(format_date(UNIX_EPOCH), "Thu, 01 Jan 1970 00:00:00 +0000");
+    }
+
+    #[test]
+    fn header_encoding() {
+        assert_eq!(encode_header_text("Hello\r\nBcc: x"), "Hello  Bcc: x");
+        assert_eq!(encode_header_text("Grüße"), "=?utf-8?b?R3LDvMOfZQ==?=");
+
+        let mailbox = Mailbox::with_name("Test \"Product\"", "no-reply@test.com");
+        assert_eq!(
+            mailbox.to_string(),
+            "\"Test \\\"Product\\\"\" <no-reply@test.com>"
+        );
+        let mailbox = Mailbox::with_name("Jürgen", "j@test.com");
+        assert_eq!(mailbox.to_string(), "=?utf-8?b?SsO8cmdlbg==?= <j@test.com>");
+    }
+
+    #[test]
+    fn header_folding() {
+        let value = "Your  order\tof ".repeat(12) + "items";
+        let mut out = String::new();
+        write_header(&mut out, "Subject", &value);
+        assert!(out.lines().count() > 1);
+        assert!(out.split("\r\n").all(|line| line.len() <= 78));
+        // unfolding restores the value with its whitespace
+        assert_eq!(out.replace("\r\n", ""), format!("Subject: {value}"));
+
+        // words that don't fit on a line are split into encoded words
+        let word = "x".repeat(1200);
+        let mut out = String::new();
+        write_header(&mut out, "Subject", &format!("Order {word}"));
+        assert!(out.split("\r\n").all(|line| line.len() <= 78));
+        assert!(out.starts_with("Subject: Order\r\n =?utf-8?b?"));
+        let decoded: String = out
+            .split_whitespace()
+            .filter_map(|word| word.strip_prefix("=?utf-8?b?")?.strip_suffix("?="))
+            .map(|encoded| String::from_utf8(BASE64.decode(encoded).unwrap()).unwrap())
+            .collect();
+        assert_eq!(decoded, word);
+    }
+
+    #[test]
+    #[cfg(feature = "lettre")]
+    fn link_host() {
+        use super::host;
+
+        assert_eq!(host("https://test.com"), "test.com");
+        assert_eq!(host("https://user@test.com:8080/path?query"), "test.com");
+        assert_eq!(host("test.com/path"), "test.com");
+        assert_eq!(host(""), "localhost");
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+/// Built-in strings of emails in one language
+///
+/// Used for everything an email or the branding doesn't set explicitly: the default greeting,
+/// greeting by name, signature, copyright notice, the action fallback text and the callout labels.
+/// English, German, French and Spanish are bundled, see [`Strings::bundled`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Strings {
+    /// Greeting of [`Greeting::Default`](crate::Greeting::Default)
+    pub greeting: String,
+    /// Greeting of [`Greeting::Name`](crate::Greeting::Name), `{name}` is replaced with the name
+    pub greeting_name: String,
+    /// Signature if the email doesn't set one
+    pub signature: String,
+    /// Copyright notice if the branding doesn't set one, `{name}` is replaced with the product name
+    pub copyright: String,
+    /// Text displayed above the link of an action in case the button doesn't work,
+    /// `{ACTION}` is replaced with the text of the action
+    pub trouble_text: String,
+    /// Label of [`Severity::Info`](crate::Severity::Info) callouts in the text version
+    pub info: String,
+    /// Label of [`Severity::Success`](crate::Severity::Success) callouts in the text version
+    pub success: String,
+    /// Label of [`Severity::Warning`](crate::Severity::Warning) callouts in the text version
+    pub warning: String,
+    /// Label of [`Severity::Danger`](crate::Severity::Danger) callouts in the text version
+    pub danger: String,
+}
+
+impl Strings {
+    pub fn english() -> Self {
+        Self {
+            greeting: "Hey".to_string(),
+            greeting_name: "Hey {name},".to_string(),
+            signature: "Yours truly,".to_string(),
+            copyright: "Copyright © {name}. All rights reserved.".to_string(),
+            trouble_text: "If you're having trouble with the button '{ACTION}', copy and paste the URL below into your web browser.".to_string(),
+            info: "INFO".to_string(),
+            success: "SUCCESS".to_string(),
+            warning: "WARNING".to_string(),
+            danger: "DANGER".to_string(),
+        }
+    }
+
+    pub fn german() -> Self {
+        Self {
+            greeting: "Hallo".to_string(),
+            greeting_name: "Hallo {name},".to_string(),
+            signature: "Viele Grüße,".to_string(),
+            copyright: "Copyright © {name}. Alle Rechte vorbehalten.".to_string(),
+            trouble_text: "Falls der Button '{ACTION}' nicht funktioniert, kopiere die folgende URL in deinen Webbrowser.".to_string(),
+            info: "HINWEIS".to_string(),
+            success: "ERFOLG".to_string(),
+            warning: "WARNUNG".to_string(),
+            danger: "ACHTUNG".to_string(),
+        }
+    }
+
+    pub fn french() -> Self {
+        Self {
+            greeting: "Bonjour".to_string(),
+            greeting_name: "Bonjour {name},".to_string(),
+            signature: "Cordialement,".to_string(),
+            copyright: "Copyright © {name}. Tous droits réservés.".to_string(),
+            trouble_text: "Si le bouton '{ACTION}' ne fonctionne pas, copiez et collez l'URL ci-dessous dans votre navigateur.".to_string(),
+            info: "INFO".to_string(),
+            success: "SUCCÈS".to_string(),
+            warning: "ATTENTION".to_string(),
+            danger: "DANGER".to_string(),
+        }
+    }
+
+    pub fn spanish() -> Self {
+        Self {
+            greeting: "Hola".to_string(),
+            greeting_name: "Hola {name}:".to_string(),
+            signature: "Atentamente,".to_string(),
+            copyright: "Copyright © {name}. Todos los derechos reservados.".to_string(),
+            trouble_text: "Si tienes problemas con el botón '{ACTION}', copia y pega la siguiente URL en tu navegador.".to_string(),
+            info: "INFORMACIÓN".to_string(),
+            success: "ÉXITO".to_string(),
+            warning: "ADVERTENCIA".to_string(),
+            danger: "PELIGRO".to_string(),
+        }
+    }
+
+    /// Bundled strings of a language (`en`, `de`, `fr` or `es`)
+    pub fn bundled(language: &str) -> Option<Self> {
+        match language {
+            "en" => Some(Self::english()),
+            "de" => Some(Self::german()),
+            "fr" => Some(Self::french()),
+            "es" => Some(Self::spanish()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+/// Normalizes a locale (e.g. `de_CH` → `de-ch`) and returns it with its language as fallback
+pub(crate) fn candidates(locale: &str) -> [String; 2] {
+    let locale = locale.trim().replace('_', "-").to_ascii_lowercase();
+    let language = locale.split('-').next().unwrap_or_default().to_string();
+
+    [locale, language]
+}
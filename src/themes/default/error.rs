@@ -6,6 +6,10 @@ pub enum Error {
     Render(#[from] minijinja::Error),
     #[error("css inlining error: {0}")]
     InlineCSS(#[from] css_inline::InlineError),
+    #[error("json-ld serialization error: {0}")]
+    JsonLd(#[from] serde_json::Error),
     #[error("html to text error: {0}")]
     HtmlToText(#[from] html2text::Error),
+    #[error("template loading error: {0}")]
+    Io(#[from] std::io::Error),
 }
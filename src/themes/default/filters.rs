@@ -0,0 +1,49 @@
+//! Filters used by the default theme templates to escape values for their context
+
+use minijinja::value::ViaDeserialize;
+use minijinja::{State, Value};
+
+use crate::Text;
+
+/// Replaces URLs with a scheme that isn't explicitly allowed (e.g. `javascript:`) with `#`.
+/// The result still gets HTML escaped by the template.
+pub fn url(value: &str) -> String {
+    crate::url::sanitize(value)
+}
+
+/// Renders a [`Text`] of the email: HTML is marked safe, everything else gets escaped
+/// by the template. Plain strings are passed through.
+pub fn text(text: ViaDeserialize<Text<'static>>) -> Value {
+    match text.0 {
+        Text::Html(html) => Value::from_safe_string(html.into_owned()),
+        text => Value::from(text.to_string()),
+    }
+}
+
+/// Mirrors `left` and `right` (e.g. in alignments or CSS properties) in right-to-left emails.
+pub fn mirror(state: &State, value: &str) -> String {
+    let rtl = state
+        .lookup("direction")
+        .is_some_and(|direction| direction.as_str() == Some("rtl"));
+
+    match value {
+        "left" if rtl => "right".to_string(),
+        "right" if rtl => "left".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// Strips characters that could break out of a CSS property value (e.g. `;`, `{`, `:`).
+/// The result contains no HTML special characters besides `'` and is safe to use in
+/// `<style>` tags (where entities aren't decoded) and double quoted attributes.
+pub fn css(value: &str) -> Value {
+    let value: String = value
+        .chars()
+        .filter(|c| {
+            c.is_ascii_alphanumeric()
+                || matches!(c, '#' | '(' | ')' | ',' | '.' | '%' | ' ' | '-' | '\'')
+        })
+        .collect();
+
+    Value::from_safe_string(value)
+}
@@ -1,20 +1,33 @@
 mod error;
+mod filters;
+mod style;
+mod text_list;
+mod text_table;
 
-use minijinja::Environment;
+use std::path::Path;
+
+use minijinja::{path_loader, AutoEscape, Environment, Value};
 use serde::Serialize;
 
 pub use self::error::Error;
+pub use self::style::{DarkStyle, ThemeStyle};
 use super::{TemplateContext, Theme};
+use crate::Direction;
 
 static HTML: &str = include_str!("template.html");
 static TEXT: &str = include_str!("template.text");
 
+/// Line width of the text version
+const TEXT_WIDTH: usize = 80;
+
 #[derive(Serialize)]
 struct DefaultThemeContext<'a> {
     #[serde(flatten)]
     context: &'a TemplateContext<'a>,
 
     logo_max_height: u32,
+    style: &'a ThemeStyle,
+    json_ld: Option<Value>,
 }
 
 #[derive(Debug, Clone)]
@@ -22,19 +35,71 @@ pub struct DefaultTheme {
     environment: Environment<'static>,
 
     pub logo_max_height: u32,
+    pub style: ThemeStyle,
 }
 
 impl DefaultTheme {
     pub fn new() -> Result<Self, Error> {
-        let mut environment = Environment::new();
+        let mut environment = Self::environment()?;
         environment.add_template("html", HTML)?;
         environment.add_template("text", TEXT)?;
 
-        Ok(Self {
+        Ok(Self::with_environment(environment))
+    }
+
+    /// Theme rendering custom templates with the filters, CSS inlining and text conversion of the default theme.
+    ///
+    /// The text template is rendered as HTML and converted to plain text afterwards.
+    /// Texts of the email (e.g. `block.text`) are rendered with the `text` filter, the localized
+    /// greeting is available as `greeting` and the callout labels as `severities[block.severity]`.
+    /// Templates can `{% extends "default.html" %}` (or `"default.text"`) and override single blocks
+    /// of the default templates, e.g. `masthead`, `content`, `action` or `footer`.
+    pub fn from_templates<S: Into<String>>(html: S, text: S) -> Result<Self, Error> {
+        let mut environment = Self::environment()?;
+        environment.add_template_owned("html", html.into())?;
+        environment.add_template_owned("text", text.into())?;
+
+        Ok(Self::with_environment(environment))
+    }
+
+    /// Theme rendering `template.html` and `template.text` from a directory,
+    /// other templates in the directory can be included or extended by them.
+    pub fn from_directory<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let html = std::fs::read_to_string(path.join("template.html"))?;
+        let text = std::fs::read_to_string(path.join("template.text"))?;
+
+        let mut environment = Self::environment()?;
+        environment.set_loader(path_loader(path));
+        environment.add_template_owned("html", html)?;
+        environment.add_template_owned("text", text)?;
+
+        Ok(Self::with_environment(environment))
+    }
+
+    fn environment() -> Result<Environment<'static>, Error> {
+        let mut environment = Environment::new();
+        // the text template is converted by html2text as well, so both are escaped as html
+        environment.set_auto_escape_callback(|_| AutoEscape::Html);
+        environment.add_filter("text", filters::text);
+        environment.add_filter("url", filters::url);
+        environment.add_filter("css", filters::css);
+        environment.add_filter("mirror", filters::mirror);
+        environment.add_function("text_list", text_list::text_list);
+        environment.add_function("text_table", text_table::text_table);
+        environment.add_template("default.html", HTML)?;
+        environment.add_template("default.text", TEXT)?;
+
+        Ok(environment)
+    }
+
+    fn with_environment(environment: Environment<'static>) -> Self {
+        Self {
             environment,
 
             logo_max_height: 50,
-        })
+            style: ThemeStyle::default(),
+        }
     }
 
     fn render(&self, template: &str, context: &TemplateContext) -> Result<String, Error> {
@@ -42,6 +107,8 @@ impl DefaultTheme {
             context,
 
             logo_max_height: self.logo_max_height,
+            style: &self.style,
+            json_ld: context.email.json_ld()?.map(Value::from_safe_string),
         };
 
         let rendered = self.environment.get_template(template)?.render(context)?;
@@ -54,15 +121,37 @@ impl Theme for DefaultTheme {
 
     fn html(&self, context: &TemplateContext) -> Result<String, Self::Error> {
         let html = self.render("html", context)?;
-        let html = css_inline::inline(&html)?;
+        // media queries (responsive layout, dark mode) can't be inlined and are kept in a style tag
+        let inliner = css_inline::CSSInliner::options()
+            .keep_at_rules(true)
+            .build();
+        let html = inliner.inline(&html)?;
 
         Ok(html)
     }
 
     fn text(&self, context: &TemplateContext) -> Result<String, Self::Error> {
         let text = self.render("text", context)?;
-        let text = html2text::from_read(text.as_bytes(), 80)?;
+        let text = html2text::from_read(text.as_bytes(), TEXT_WIDTH)?;
+
+        if context.direction == Direction::Rtl {
+            return Ok(right_to_left(&text));
+        }
 
         Ok(text)
     }
 }
+
+/// Starts every line with a right-to-left mark, so clients lay out lines starting
+/// with neutral or left-to-right characters (numbers, urls, tables) right-to-left as well
+fn right_to_left(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("\u{200f}{line}\n")
+            }
+        })
+        .collect()
+}
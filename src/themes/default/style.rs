@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+/// Colors and typography of the default theme
+///
+/// Colors and the font stack are CSS values, characters that could break out of
+/// a CSS property (e.g. `;` or `{`) are stripped when rendering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeStyle {
+    /// Color of links
+    pub primary_color: String,
+    /// Color of headings and emphasized text
+    pub secondary_color: String,
+    /// Color of the body text
+    pub text_color: String,
+    /// Background color around the email body
+    pub background_color: String,
+    /// Background color of the email body
+    pub content_background_color: String,
+    /// Default background color of action buttons
+    pub button_color: String,
+    /// Default text color of action buttons
+    pub button_text_color: String,
+    /// Accent color of info callouts
+    pub info_color: String,
+    /// Accent color of success callouts
+    pub success_color: String,
+    /// Accent color of warning callouts
+    pub warning_color: String,
+    /// Accent color of danger callouts
+    pub danger_color: String,
+    /// Font stack used for all text
+    pub font_family: String,
+    /// Width of the email body in pixels
+    pub body_width: u32,
+    /// Border radius of action buttons, verification codes and callouts in pixels
+    pub border_radius: u32,
+    /// Palette used when the email client is in dark mode, `None` opts out of dark mode
+    pub dark: Option<DarkStyle>,
+}
+
+impl Default for ThemeStyle {
+    fn default() -> Self {
+        Self {
+            primary_color: "#3869D4".to_string(),
+            secondary_color: "#2F3133".to_string(),
+            text_color: "#74787E".to_string(),
+            background_color: "#F2F4F6".to_string(),
+            content_background_color: "#FFF".to_string(),
+            button_color: "#3869D4".to_string(),
+            button_text_color: "#ffffff".to_string(),
+            info_color: "#3869D4".to_string(),
+            success_color: "#22BC66".to_string(),
+            warning_color: "#E8A317".to_string(),
+            danger_color: "#DC4D2F".to_string(),
+            font_family: "Arial, 'Helvetica Neue', Helvetica, sans-serif".to_string(),
+            body_width: 570,
+            border_radius: 3,
+            dark: Some(DarkStyle::default()),
+        }
+    }
+}
+
+/// Colors of the default theme in dark mode (`prefers-color-scheme: dark`)
+///
+/// Actions with custom colors keep them in dark mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DarkStyle {
+    /// Color of links
+    pub primary_color: String,
+    /// Color of headings and emphasized text
+    pub secondary_color: String,
+    /// Color of the body text
+    pub text_color: String,
+    /// Background color around the email body
+    pub background_color: String,
+    /// Background color of the email body
+    pub content_background_color: String,
+    /// Default background color of action buttons
+    pub button_color: String,
+    /// Default text color of action buttons
+    pub button_text_color: String,
+}
+
+impl Default for DarkStyle {
+    fn default() -> Self {
+        Self {
+            primary_color: "#7BA4F4".to_string(),
+            secondary_color: "#E8EAED".to_string(),
+            text_color: "#B8BCC2".to_string(),
+            background_color: "#121212".to_string(),
+            content_background_color: "#1E1E1E".to_string(),
+            button_color: "#3869D4".to_string(),
+            button_text_color: "#ffffff".to_string(),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Plain text rendering of lists with `- ` or `1. ` markers.
+//! Long items are wrapped with their continuation lines indented under the marker.
+
+use minijinja::value::ViaDeserialize;
+use serde::Deserialize;
+use unicode_width::UnicodeWidthStr;
+
+use super::text_table::{plain, wrap};
+use super::TEXT_WIDTH;
+use crate::Text;
+
+/// Indentation of nested items
+const INDENT: &str = "   ";
+
+#[derive(Deserialize)]
+pub struct List {
+    ordered: bool,
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    text: Text<'static>,
+    children: Vec<Text<'static>>,
+}
+
+/// Renders a list for the text template
+pub fn text_list(list: ViaDeserialize<List>) -> String {
+    let marker = |index: usize| {
+        if list.ordered {
+            format!("{}. ", index + 1)
+        } else {
+            "- ".to_string()
+        }
+    };
+
+    let mut lines = Vec::new();
+    for (index, item) in list.items.iter().enumerate() {
+        push_item(&mut lines, "", &marker(index), &item.text);
+        for (index, child) in item.children.iter().enumerate() {
+            push_item(&mut lines, INDENT, &marker(index), child);
+        }
+    }
+    lines.join("\n")
+}
+
+/// Wraps an item to the text width, continuation lines are aligned with the text after the marker
+fn push_item(lines: &mut Vec<String>, indent: &str, marker: &str, text: &Text) {
+    let hanging = " ".repeat(indent.width() + marker.width());
+    let width = TEXT_WIDTH.saturating_sub(hanging.len()).max(1);
+
+    for (line, wrapped) in wrap(&plain(text), width).into_iter().enumerate() {
+        match line {
+            0 => lines.push(format!("{indent}{marker}{wrapped}")),
+            _ => lines.push(format!("{hanging}{wrapped}")),
+        }
+    }
+}
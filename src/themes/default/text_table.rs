@@ -0,0 +1,310 @@
+//! Plain text rendering of tables as aligned monospace grids.
+//! Tables too wide for the text width fall back to stacked key/value blocks.
+
+use minijinja::value::ViaDeserialize;
+use serde::Deserialize;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::TEXT_WIDTH;
+use crate::Text;
+
+const SEPARATOR: &str = " | ";
+const RULE_SEPARATOR: &str = "-+-";
+
+#[derive(Deserialize)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<Cell>>,
+    footer: Vec<Vec<Cell>>,
+}
+
+#[derive(Deserialize)]
+struct Column {
+    #[serde(deserialize_with = "plain_label")]
+    label: String,
+    align: String,
+}
+
+#[derive(Deserialize)]
+struct Cell {
+    text: Text<'static>,
+    link: Option<String>,
+    colspan: usize,
+}
+
+/// Text as a single line, trusted html (e.g. rendered markdown) is converted to text
+pub(super) fn plain(text: &Text) -> String {
+    match text {
+        Text::Html(html) => html2text::config::plain_no_decorate()
+            .link_footnotes(false)
+            .string_from_read(html.as_bytes(), usize::MAX)
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_else(|_| html.to_string()),
+        text => text.to_string(),
+    }
+}
+
+fn plain_label<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Text::deserialize(deserializer).map(|label| plain(&label))
+}
+
+impl Cell {
+    fn content(&self) -> String {
+        let text = plain(&self.text);
+        match &self.link {
+            Some(link) => format!("{text} ({})", crate::url::sanitize(link)),
+            None => text,
+        }
+    }
+}
+
+/// Cell of a row placed on the grid
+struct Placed {
+    column: usize,
+    span: usize,
+    content: String,
+}
+
+/// Renders a table for the text template
+pub fn text_table(table: ViaDeserialize<Table>) -> String {
+    let table = &*table;
+    if table.columns.is_empty() {
+        return String::new();
+    }
+
+    match column_widths(table, TEXT_WIDTH) {
+        Some(widths) => grid(table, &widths),
+        None => stacked(table, TEXT_WIDTH),
+    }
+}
+
+/// Places the cells of a row on the columns, dropping cells outside of the table
+fn place(row: &[Cell], columns: usize) -> Vec<Placed> {
+    let mut placed = Vec::with_capacity(row.len());
+    let mut column = 0;
+    for cell in row {
+        if column >= columns {
+            break;
+        }
+        let span = cell.colspan.clamp(1, columns - column);
+        placed.push(Placed {
+            column,
+            span,
+            content: cell.content(),
+        });
+        column += span;
+    }
+    placed
+}
+
+fn longest_word(text: &str) -> usize {
+    text.split_whitespace().map(str::width).max().unwrap_or(0)
+}
+
+/// Widths of the columns so the grid fits into `max_width`, `None` if it doesn't fit even with wrapping
+fn column_widths(table: &Table, max_width: usize) -> Option<Vec<usize>> {
+    let columns = table.columns.len();
+    let mut natural: Vec<usize> = table.columns.iter().map(|c| c.label.width()).collect();
+    let mut minimum: Vec<usize> = table
+        .columns
+        .iter()
+        .map(|c| longest_word(&c.label).max(1))
+        .collect();
+
+    for row in table.rows.iter().chain(&table.footer) {
+        for cell in place(row, columns) {
+            // spanning cells wrap within the columns they cover
+            if cell.span == 1 {
+                natural[cell.column] = natural[cell.column].max(cell.content.width());
+                minimum[cell.column] = minimum[cell.column].max(longest_word(&cell.content));
+            }
+        }
+    }
+
+    let available = max_width.checked_sub(SEPARATOR.len() * (columns - 1))?;
+    if natural.iter().sum::<usize>() <= available {
+        return Some(natural);
+    }
+    if minimum.iter().sum::<usize>() > available {
+        return None;
+    }
+
+    // columns that are closest to their natural width are widened first,
+    // so short columns don't wrap and the remaining space goes to the long ones
+    let mut widths = minimum;
+    let mut remaining = available - widths.iter().sum::<usize>();
+    let mut order: Vec<usize> = (0..columns).collect();
+    order.sort_by_key(|&column| natural[column].saturating_sub(widths[column]));
+    for column in order {
+        let grant = natural[column]
+            .saturating_sub(widths[column])
+            .min(remaining);
+        widths[column] += grant;
+        remaining -= grant;
+    }
+    Some(widths)
+}
+
+/// Word wraps text to `width`, breaking words that are longer than the width
+pub(super) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let separator = usize::from(!line.is_empty());
+        if !line.is_empty() && line.width() + separator + word.width() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        if word.width() > width {
+            for c in word.chars() {
+                if line.width() + c.width().unwrap_or(0) > width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                line.push(c);
+            }
+        } else {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn align(text: &str, width: usize, alignment: &str) -> String {
+    let padding = width.saturating_sub(text.width());
+    let (left, right) = match alignment {
+        "right" => (padding, 0),
+        "center" => (padding / 2, padding - padding / 2),
+        _ => (0, padding),
+    };
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(right))
+}
+
+fn grid(table: &Table, widths: &[usize]) -> String {
+    let columns = table.columns.len();
+    let span_width = |column: usize, span: usize| {
+        widths[column..column + span].iter().sum::<usize>() + SEPARATOR.len() * (span - 1)
+    };
+
+    let render_row = |cells: Vec<Placed>, lines: &mut Vec<String>| {
+        let mut cells = cells;
+        // pad short rows with empty cells so the separators line up
+        let filled: usize = cells.iter().map(|cell| cell.span).sum();
+        cells.extend((filled..columns).map(|column| Placed {
+            column,
+            span: 1,
+            content: String::new(),
+        }));
+
+        let wrapped: Vec<Vec<String>> = cells
+            .iter()
+            .map(|cell| wrap(&cell.content, span_width(cell.column, cell.span)))
+            .collect();
+        let height = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+
+        for line in 0..height {
+            let rendered: Vec<String> = cells
+                .iter()
+                .zip(&wrapped)
+                .map(|(cell, wrapped)| {
+                    align(
+                        wrapped.get(line).map_or("", String::as_str),
+                        span_width(cell.column, cell.span),
+                        &table.columns[cell.column].align,
+                    )
+                })
+                .collect();
+            lines.push(rendered.join(SEPARATOR).trim_end().to_string());
+        }
+    };
+
+    let rule = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>()
+        .join(RULE_SEPARATOR);
+
+    let mut lines = Vec::new();
+    let header = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(column, c)| Placed {
+            column,
+            span: 1,
+            content: c.label.clone(),
+        })
+        .collect();
+    render_row(header, &mut lines);
+    lines.push(rule.clone());
+
+    for row in &table.rows {
+        render_row(place(row, columns), &mut lines);
+    }
+
+    if !table.footer.is_empty() {
+        lines.push(rule);
+        for row in &table.footer {
+            render_row(place(row, columns), &mut lines);
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Renders every row as a block of `label: value` lines
+fn stacked(table: &Table, width: usize) -> String {
+    let columns = table.columns.len();
+    let mut blocks = Vec::new();
+
+    for row in &table.rows {
+        let block: Vec<String> = place(row, columns)
+            .into_iter()
+            .filter(|cell| !cell.content.is_empty())
+            .map(|cell| match cell.span {
+                1 => format!("{}: {}", table.columns[cell.column].label, cell.content),
+                _ => cell.content,
+            })
+            .collect();
+        blocks.push(block);
+    }
+
+    if !table.footer.is_empty() {
+        let block = table
+            .footer
+            .iter()
+            .map(|row| {
+                let cells: Vec<String> = place(row, columns)
+                    .into_iter()
+                    .map(|cell| cell.content)
+                    .filter(|content| !content.is_empty())
+                    .collect();
+                match cells.split_first() {
+                    Some((first, [])) => first.clone(),
+                    Some((first, rest)) => format!("{first}: {}", rest.join(" ")),
+                    None => String::new(),
+                }
+            })
+            .collect();
+        blocks.push(block);
+    }
+
+    blocks
+        .iter()
+        .map(|block| {
+            block
+                .iter()
+                .flat_map(|line| wrap(line, width))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
@@ -2,10 +2,12 @@
 mod default;
 
 #[cfg(feature = "default-theme")]
-pub use default::DefaultTheme;
+pub use default::{DarkStyle, DefaultTheme, ThemeStyle};
+use std::collections::HashMap;
+
 use serde::Serialize;
 
-use crate::{Branding, Email};
+use crate::{Branding, Direction, Email, Severity};
 
 pub trait Theme {
     type Error: std::error::Error;
@@ -18,4 +20,10 @@ pub trait Theme {
 pub struct TemplateContext<'a> {
     pub branding: &'a Branding,
     pub email: &'a Email<'a>,
+    /// Greeting of the email in its locale
+    pub greeting: Option<&'a str>,
+    /// Labels of the callout severities in the locale of the email
+    pub severities: &'a HashMap<Severity, String>,
+    /// Text direction of the email
+    pub direction: Direction,
 }
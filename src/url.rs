@@ -0,0 +1,30 @@
+//! Sanitizing of URLs in links and image sources
+
+/// URL schemes that are allowed in links and image sources
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// Replaces URLs with a scheme that isn't explicitly allowed (e.g. `javascript:`) with `#`.
+/// The result isn't escaped.
+pub(crate) fn sanitize(value: &str) -> String {
+    // browsers ignore whitespace and control characters while parsing the scheme
+    let normalized: String = value
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect();
+
+    let scheme = normalized
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| {
+            scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        });
+
+    match scheme {
+        Some(scheme) if !ALLOWED_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()) => {
+            "#".to_string()
+        }
+        _ => value.trim().to_string(),
+    }
+}